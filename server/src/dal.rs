@@ -2,10 +2,24 @@ use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
 use sqlx::{FromRow, SqliteExecutor};
 
+#[derive(Debug, FromRow)]
+pub struct UserEntity {
+    pub id: i64,
+    pub username: String,
+    pub password_hash: String,
+}
+
 #[derive(Debug, FromRow)]
 pub struct ExerciseEntity {
     pub id: i64,
     pub name: String,
+    pub has_image: bool,
+}
+
+#[derive(Debug, FromRow)]
+pub struct ExerciseImageEntity {
+    pub content_type: String,
+    pub data: Vec<u8>,
 }
 
 #[derive(Debug, FromRow)]
@@ -21,6 +35,94 @@ pub struct SetSuggestionEntity {
     pub exercise_id: i64,
     pub repetitions: i64,
     pub weight: i64,
+    pub weight_unit: WeightUnit,
+    pub e1rm: f64,
+}
+
+/// Fraction a working weight is bumped by once the lifter has outgrown it.
+const PROGRESSION_FACTOR: f64 = 0.025;
+
+/// Number of most recent sets of an exercise considered when judging whether
+/// the lifter is consistently hitting their rep target.
+const PROGRESSION_LOOKBACK_SETS: i64 = 3;
+
+/// One pound expressed in kilograms, used to normalize weights logged in
+/// different units onto a common scale before comparing or averaging them.
+const KG_PER_LB: f64 = 0.45359237;
+
+/// Unit a set's `weight` was logged in. Stored alongside the set so that
+/// users can switch units without corrupting previously logged history.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, sqlx::Type)]
+#[sqlx(type_name = "TEXT", rename_all = "lowercase")]
+pub enum WeightUnit {
+    Kg,
+    Lb,
+}
+
+impl WeightUnit {
+    /// Converts `weight`, expressed in this unit, to kilograms.
+    fn to_kg(self, weight: i64) -> f64 {
+        match self {
+            WeightUnit::Kg => weight as f64,
+            WeightUnit::Lb => weight as f64 * KG_PER_LB,
+        }
+    }
+
+    /// Converts `weight_kg` to this unit, keeping fractional precision.
+    fn from_kg_f64(self, weight_kg: f64) -> f64 {
+        match self {
+            WeightUnit::Kg => weight_kg,
+            WeightUnit::Lb => weight_kg / KG_PER_LB,
+        }
+    }
+
+    /// Converts `weight_kg` to this unit, rounded to a whole unit for storage.
+    fn from_kg(self, weight_kg: f64) -> i64 {
+        self.from_kg_f64(weight_kg).round() as i64
+    }
+
+    /// Smallest weight increment suggestions are rounded to in this unit,
+    /// e.g. the smallest plate pair commonly available.
+    fn plate_increment(self) -> f64 {
+        match self {
+            WeightUnit::Kg => 2.5,
+            WeightUnit::Lb => 5.0,
+        }
+    }
+}
+
+/// Estimates the one-rep-max for a single set, averaging Epley and Brzycki
+/// for rep ranges where Brzycki is still considered accurate. `weight_kg`
+/// must already be normalized to kilograms so that sets logged in different
+/// units can be compared and averaged together.
+fn estimate_one_rep_max(weight_kg: f64, repetitions: i64) -> f64 {
+    if repetitions <= 1 {
+        return weight_kg;
+    }
+
+    let epley = weight_kg * (1.0 + repetitions as f64 / 30.0);
+
+    if repetitions <= 12 {
+        let brzycki = weight_kg * 36.0 / (37.0 - repetitions as f64);
+        (epley + brzycki) / 2.0
+    } else {
+        epley
+    }
+}
+
+/// Estimates the one-rep-max for a single set with the plain Epley formula,
+/// treating a 1-rep set as the weight itself. `weight_kg` must already be
+/// normalized to kilograms.
+fn epley_one_rep_max(weight_kg: f64, repetitions: i64) -> f64 {
+    if repetitions <= 1 {
+        weight_kg
+    } else {
+        weight_kg * (1.0 + repetitions as f64 / 30.0)
+    }
+}
+
+fn round_to_plate_increment(weight: f64, increment: f64) -> i64 {
+    ((weight / increment).round() * increment).round() as i64
 }
 
 #[derive(Debug, FromRow)]
@@ -33,6 +135,7 @@ pub struct ExerciseSetEntity {
     pub created: DateTime<Utc>,
     pub repetitions: i64,
     pub weight: i64,
+    pub weight_unit: WeightUnit,
     pub note: Option<String>,
 }
 
@@ -51,123 +154,284 @@ pub struct StatisticsOverviewEntity {
     pub avg_repetitions_per_set: i64,
 }
 
-pub async fn get_exercise_count<'local, E>(conn: E, id: i64) -> Result<ExerciseCountEntity>
+pub async fn create_user<'local, E>(conn: E, username: &str, password_hash: &str) -> Result<UserEntity>
 where
     E: SqliteExecutor<'local>,
 {
-    sqlx::query_as("SELECT COUNT(*) AS count FROM exercise_set WHERE exercise_id = ?")
-        .bind(id)
-        .fetch_one(conn)
-        .await
-        .with_context(|| format!("Failed to get exercise count for exercise with id {id}"))
+    sqlx::query_as(
+        "INSERT INTO users (username, password_hash) VALUES (?, ?) RETURNING id, username, password_hash",
+    )
+    .bind(username)
+    .bind(password_hash)
+    .fetch_one(conn)
+    .await
+    .with_context(|| format!(r#"Failed to create user with username "{username}""#))
 }
 
-pub async fn get_exercise<'local, E>(conn: E, id: i64) -> Result<Option<ExerciseEntity>>
+pub async fn get_user_by_username<'local, E>(conn: E, username: &str) -> Result<Option<UserEntity>>
 where
     E: SqliteExecutor<'local>,
 {
-    sqlx::query_as("SELECT id, name FROM exercise WHERE id = ?")
-        .bind(id)
+    sqlx::query_as("SELECT id, username, password_hash FROM users WHERE username = ?")
+        .bind(username)
         .fetch_optional(conn)
         .await
-        .with_context(|| format!("Failed to get exercise with id {id}"))
+        .with_context(|| format!(r#"Failed to get user with username "{username}""#))
 }
 
-pub async fn get_exercises<'local, E>(conn: E) -> Result<Vec<ExerciseEntity>>
+pub async fn get_exercise_count<'local, E>(conn: E, user_id: i64, id: i64) -> Result<ExerciseCountEntity>
 where
     E: SqliteExecutor<'local>,
 {
-    sqlx::query_as("SELECT id, name FROM exercise ORDER BY name")
-        .fetch_all(conn)
-        .await
-        .context("Failed to get exercises")
+    sqlx::query_as(
+        "
+        SELECT COUNT(*) AS count FROM exercise_set
+        WHERE user_id = ? AND exercise_id = ? AND deleted_utc_s IS NULL
+        ",
+    )
+    .bind(user_id)
+    .bind(id)
+    .fetch_one(conn)
+    .await
+    .with_context(|| format!("Failed to get exercise count for exercise with id {id}"))
 }
 
-pub async fn create_exercise<'local, E>(conn: E, name: &str) -> Result<ExerciseEntity>
+const HAS_IMAGE_EXPR: &str =
+    "EXISTS(SELECT 1 FROM exercise_images ei WHERE ei.exercise_id = id) AS has_image";
+
+pub async fn get_exercise<'local, E>(conn: E, user_id: i64, id: i64) -> Result<Option<ExerciseEntity>>
 where
     E: SqliteExecutor<'local>,
 {
-    sqlx::query_as("INSERT INTO exercise (name) VALUES (?) RETURNING id, name")
-        .bind(name)
-        .fetch_one(conn)
-        .await
-        .with_context(|| format!(r#"Failed to create exercise with name "{name}""#))
+    sqlx::query_as(&format!(
+        "SELECT id, name, {HAS_IMAGE_EXPR} FROM exercise WHERE user_id = ? AND id = ? AND deleted_utc_s IS NULL"
+    ))
+    .bind(user_id)
+    .bind(id)
+    .fetch_optional(conn)
+    .await
+    .with_context(|| format!("Failed to get exercise with id {id}"))
 }
 
-pub async fn delete_exercise<'local, E>(conn: E, id: i64) -> Result<Option<()>>
+pub async fn get_exercises<'local, E>(conn: E, user_id: i64) -> Result<Vec<ExerciseEntity>>
 where
     E: SqliteExecutor<'local>,
 {
-    sqlx::query("DELETE FROM exercise WHERE id = ?")
-        .bind(id)
-        .execute(conn)
-        .await
-        .map(|res| (res.rows_affected() > 0).then_some(()))
-        .with_context(|| format!("Failed to delete exercise with id {id}"))
+    sqlx::query_as(&format!(
+        "SELECT id, name, {HAS_IMAGE_EXPR} FROM exercise WHERE user_id = ? AND deleted_utc_s IS NULL ORDER BY name"
+    ))
+    .bind(user_id)
+    .fetch_all(conn)
+    .await
+    .context("Failed to get exercises")
 }
 
-pub async fn update_exercise<'local, E>(conn: E, id: i64, name: &str) -> Result<ExerciseEntity>
+pub async fn create_exercise<'local, E>(conn: E, user_id: i64, name: &str) -> Result<ExerciseEntity>
 where
     E: SqliteExecutor<'local>,
 {
-    sqlx::query_as("UPDATE exercise SET name = ? WHERE id = ? RETURNING id, name")
-        .bind(name)
-        .bind(id)
-        .fetch_one(conn)
-        .await
-        .with_context(|| format!(r#"Failed to update name of exercise with id {id} to "{name}""#))
+    sqlx::query_as(&format!(
+        "INSERT INTO exercise (user_id, name) VALUES (?, ?) RETURNING id, name, {HAS_IMAGE_EXPR}"
+    ))
+    .bind(user_id)
+    .bind(name)
+    .fetch_one(conn)
+    .await
+    .with_context(|| format!(r#"Failed to create exercise with name "{name}""#))
 }
 
-pub async fn get_workout<'local, E>(conn: E, id: i64) -> Result<Option<WorkoutEntity>>
+pub async fn delete_exercise<'local, E>(conn: E, user_id: i64, id: i64) -> Result<Option<()>>
 where
     E: SqliteExecutor<'local>,
 {
-    sqlx::query_as("SELECT id, started_utc_s, note FROM workout WHERE id = ?")
-        .bind(id)
+    sqlx::query(
+        "
+        UPDATE exercise
+        SET deleted_utc_s = UNIXEPOCH(datetime())
+        WHERE user_id = ? AND id = ? AND deleted_utc_s IS NULL
+        ",
+    )
+    .bind(user_id)
+    .bind(id)
+    .execute(conn)
+    .await
+    .map(|res| (res.rows_affected() > 0).then_some(()))
+    .with_context(|| format!("Failed to delete exercise with id {id}"))
+}
+
+pub async fn restore_exercise<'local, E>(conn: E, user_id: i64, id: i64) -> Result<Option<ExerciseEntity>>
+where
+    E: SqliteExecutor<'local>,
+{
+    sqlx::query_as(&format!(
+        "
+        UPDATE exercise
+        SET deleted_utc_s = NULL
+        WHERE user_id = ? AND id = ? AND deleted_utc_s IS NOT NULL
+        RETURNING id, name, {HAS_IMAGE_EXPR}
+        "
+    ))
+    .bind(user_id)
+    .bind(id)
+    .fetch_optional(conn)
+    .await
+    .with_context(|| format!("Failed to restore exercise with id {id}"))
+}
+
+pub async fn update_exercise<'local, E>(
+    conn: E,
+    user_id: i64,
+    id: i64,
+    name: &str,
+) -> Result<ExerciseEntity>
+where
+    E: SqliteExecutor<'local>,
+{
+    sqlx::query_as(&format!(
+        "
+        UPDATE exercise SET name = ?
+        WHERE user_id = ? AND id = ? AND deleted_utc_s IS NULL
+        RETURNING id, name, {HAS_IMAGE_EXPR}
+        "
+    ))
+    .bind(name)
+    .bind(user_id)
+    .bind(id)
+    .fetch_one(conn)
+    .await
+    .with_context(|| format!(r#"Failed to update name of exercise with id {id} to "{name}""#))
+}
+
+pub async fn upsert_exercise_image<'local, E>(
+    conn: E,
+    user_id: i64,
+    exercise_id: i64,
+    content_type: &str,
+    data: Vec<u8>,
+) -> Result<()>
+where
+    E: SqliteExecutor<'local>,
+{
+    sqlx::query(
+        "
+        INSERT INTO exercise_images (exercise_id, user_id, content_type, data, updated_utc_s)
+        VALUES (?, ?, ?, ?, UNIXEPOCH(datetime()))
+        ON CONFLICT(exercise_id) DO UPDATE SET
+            content_type = excluded.content_type,
+            data = excluded.data,
+            updated_utc_s = excluded.updated_utc_s
+        WHERE exercise_images.user_id = ?
+        ",
+    )
+    .bind(exercise_id)
+    .bind(user_id)
+    .bind(content_type)
+    .bind(data)
+    .bind(user_id)
+    .execute(conn)
+    .await
+    .map(|_| ())
+    .with_context(|| format!("Failed to store image for exercise with id {exercise_id}"))
+}
+
+pub async fn get_exercise_image<'local, E>(
+    conn: E,
+    user_id: i64,
+    exercise_id: i64,
+) -> Result<Option<ExerciseImageEntity>>
+where
+    E: SqliteExecutor<'local>,
+{
+    sqlx::query_as("SELECT content_type, data FROM exercise_images WHERE user_id = ? AND exercise_id = ?")
+        .bind(user_id)
+        .bind(exercise_id)
         .fetch_optional(conn)
         .await
-        .with_context(|| format!("Failed to get workout with id {id}"))
+        .with_context(|| format!("Failed to get image for exercise with id {exercise_id}"))
+}
+
+pub async fn get_workout<'local, E>(conn: E, user_id: i64, id: i64) -> Result<Option<WorkoutEntity>>
+where
+    E: SqliteExecutor<'local>,
+{
+    sqlx::query_as(
+        "SELECT id, started_utc_s, note FROM workout WHERE user_id = ? AND id = ? AND deleted_utc_s IS NULL",
+    )
+    .bind(user_id)
+    .bind(id)
+    .fetch_optional(conn)
+    .await
+    .with_context(|| format!("Failed to get workout with id {id}"))
 }
 
-pub async fn get_workouts<'local, E>(conn: E) -> Result<Vec<WorkoutEntity>>
+pub async fn get_workouts<'local, E>(conn: E, user_id: i64) -> Result<Vec<WorkoutEntity>>
 where
     E: SqliteExecutor<'local>,
 {
-    sqlx::query_as("SELECT id, started_utc_s, note FROM workout")
+    sqlx::query_as("SELECT id, started_utc_s, note FROM workout WHERE user_id = ? AND deleted_utc_s IS NULL")
+        .bind(user_id)
         .fetch_all(conn)
         .await
         .context("Failed to get workouts")
 }
 
-pub async fn create_workout<'local, E>(conn: E) -> Result<WorkoutEntity>
+pub async fn create_workout<'local, E>(conn: E, user_id: i64) -> Result<WorkoutEntity>
 where
     E: SqliteExecutor<'local>,
 {
     sqlx::query_as(
         "
-        INSERT INTO workout (started_utc_s) VALUES (UNIXEPOCH(datetime()))
+        INSERT INTO workout (user_id, started_utc_s) VALUES (?, UNIXEPOCH(datetime()))
         RETURNING id, started_utc_s, note
         ",
     )
+    .bind(user_id)
     .fetch_one(conn)
     .await
     .context("Failed to create workout")
 }
 
-pub async fn delete_workout<'local, E>(conn: E, id: i64) -> Result<Option<()>>
+pub async fn delete_workout<'local, E>(conn: E, user_id: i64, id: i64) -> Result<Option<()>>
 where
     E: SqliteExecutor<'local>,
 {
-    sqlx::query("DELETE FROM workout WHERE id = ?")
-        .bind(id)
-        .execute(conn)
-        .await
-        .with_context(|| format!("Failed to delete workout with id {id}"))
-        .map(|res| (res.rows_affected() > 0).then_some(()))
+    sqlx::query(
+        "
+        UPDATE workout
+        SET deleted_utc_s = UNIXEPOCH(datetime())
+        WHERE user_id = ? AND id = ? AND deleted_utc_s IS NULL
+        ",
+    )
+    .bind(user_id)
+    .bind(id)
+    .execute(conn)
+    .await
+    .with_context(|| format!("Failed to delete workout with id {id}"))
+    .map(|res| (res.rows_affected() > 0).then_some(()))
+}
+
+pub async fn restore_workout<'local, E>(conn: E, user_id: i64, id: i64) -> Result<Option<WorkoutEntity>>
+where
+    E: SqliteExecutor<'local>,
+{
+    sqlx::query_as(
+        "
+        UPDATE workout
+        SET deleted_utc_s = NULL
+        WHERE user_id = ? AND id = ? AND deleted_utc_s IS NOT NULL
+        RETURNING id, started_utc_s, note
+        ",
+    )
+    .bind(user_id)
+    .bind(id)
+    .fetch_optional(conn)
+    .await
+    .with_context(|| format!("Failed to restore workout with id {id}"))
 }
 
 pub async fn update_workout_meta_data<'local, E>(
     conn: E,
+    user_id: i64,
     id: i64,
     note: &str,
 ) -> Result<Option<WorkoutEntity>>
@@ -183,108 +447,186 @@ where
         "
         UPDATE workout
         SET note = ?
-        WHERE id = ?
+        WHERE user_id = ? AND id = ? AND deleted_utc_s IS NULL
         RETURNING id, started_utc_s, note
         ",
     )
     .bind(note)
+    .bind(user_id)
     .bind(id)
     .fetch_optional(conn)
     .await
     .with_context(|| format!("Failed to update note for workout with id {id}"))
 }
 
-enum ExerciseSetConstraintId {
-    ExerciseSet,
-    Workout,
-    Exercise,
-}
-
-fn create_get_exercise_query(constraint: Option<ExerciseSetConstraintId>) -> String {
-    const GET_ALL_EXERCISES_QUERY: &str = "
+const GET_EXERCISE_SETS_QUERY: &str = "
     SELECT
         es.id, es.exercise_id, e.name AS exercise_name,
-        es.workout_id, es.created_utc_s, es.repetitions, es.weight, es.note
+        es.workout_id, es.created_utc_s, es.repetitions, es.weight, es.weight_unit, es.note
     FROM exercise_set es
     JOIN exercise e ON es.exercise_id = e.id
+    WHERE es.user_id = ?
+        AND es.deleted_utc_s IS NULL
+        AND e.deleted_utc_s IS NULL
 ";
 
-    match constraint {
-        Some(ExerciseSetConstraintId::ExerciseSet) => {
-            format!("{GET_ALL_EXERCISES_QUERY} WHERE es.id = ?")
-        }
-        Some(ExerciseSetConstraintId::Workout) => {
-            format!("{GET_ALL_EXERCISES_QUERY} WHERE es.workout_id = ?")
-        }
-        Some(ExerciseSetConstraintId::Exercise) => {
-            format!("{GET_ALL_EXERCISES_QUERY} WHERE es.exercise_id = ?")
-        }
-        None => GET_ALL_EXERCISES_QUERY.to_string(),
-    }
-}
-
-pub async fn get_exercise_set<'local, E>(conn: E, id: i64) -> Result<Option<ExerciseSetEntity>>
+pub async fn get_exercise_set<'local, E>(
+    conn: E,
+    user_id: i64,
+    id: i64,
+) -> Result<Option<ExerciseSetEntity>>
 where
     E: SqliteExecutor<'local>,
 {
-    sqlx::query_as(&create_get_exercise_query(Some(
-        ExerciseSetConstraintId::ExerciseSet,
-    )))
-    .bind(id)
-    .fetch_optional(conn)
-    .await
-    .with_context(|| format!("Failed to get exercise set with id {id}"))
+    sqlx::query_as(&format!("{GET_EXERCISE_SETS_QUERY} AND es.id = ?"))
+        .bind(user_id)
+        .bind(id)
+        .fetch_optional(conn)
+        .await
+        .with_context(|| format!("Failed to get exercise set with id {id}"))
 }
 
-pub async fn get_exercise_sets<'local, E>(conn: E) -> Result<Vec<ExerciseSetEntity>>
+pub async fn get_exercise_sets<'local, E>(conn: E, user_id: i64) -> Result<Vec<ExerciseSetEntity>>
 where
     E: SqliteExecutor<'local>,
 {
-    sqlx::query_as(&create_get_exercise_query(None))
-        .fetch_all(conn)
+    get_exercise_sets_filtered(conn, user_id, &ExerciseSetFilters::default())
         .await
         .context("Failed to get all exercise sets")
 }
 
 pub async fn get_exercise_sets_by_workout_id<'local, E>(
     conn: E,
+    user_id: i64,
     id: i64,
 ) -> Result<Vec<ExerciseSetEntity>>
 where
     E: SqliteExecutor<'local>,
 {
-    sqlx::query_as(&create_get_exercise_query(Some(
-        ExerciseSetConstraintId::Workout,
-    )))
-    .bind(id)
-    .fetch_all(conn)
-    .await
-    .with_context(|| format!("Failed to get exercise sets for workout with id {id}"))
+    let filters = ExerciseSetFilters {
+        workout_id: Some(id),
+        ..Default::default()
+    };
+
+    get_exercise_sets_filtered(conn, user_id, &filters)
+        .await
+        .with_context(|| format!("Failed to get exercise sets for workout with id {id}"))
 }
 
 pub async fn get_exercise_sets_by_exercise_id<'local, E>(
     conn: E,
+    user_id: i64,
     id: i64,
 ) -> Result<Vec<ExerciseSetEntity>>
 where
     E: SqliteExecutor<'local> + Copy,
 {
-    sqlx::query_as(&create_get_exercise_query(Some(
-        ExerciseSetConstraintId::Exercise,
-    )))
-    .bind(id)
-    .fetch_all(conn)
-    .await
-    .with_context(|| format!("Failed to get exercise sets for exercise with id {id}"))
+    let filters = ExerciseSetFilters {
+        exercise_id: Some(id),
+        ..Default::default()
+    };
+
+    get_exercise_sets_filtered(conn, user_id, &filters)
+        .await
+        .with_context(|| format!("Failed to get exercise sets for exercise with id {id}"))
+}
+
+/// Filters accepted by [`get_exercise_sets_filtered`]. Every field is
+/// optional; a field left at its default imposes no constraint.
+#[derive(Debug, Default)]
+pub struct ExerciseSetFilters {
+    pub exercise_id: Option<i64>,
+    pub workout_id: Option<i64>,
+    pub after: Option<DateTime<Utc>>,
+    pub before: Option<DateTime<Utc>>,
+    pub min_weight: Option<i64>,
+    pub max_weight: Option<i64>,
+    pub min_repetitions: Option<i64>,
+    pub max_repetitions: Option<i64>,
+    pub note_contains: Option<String>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+    pub reverse: bool,
+}
+
+/// Searches exercise sets matching `filters`, letting callers page through a
+/// large history by date range, weight range, or rep range instead of
+/// loading everything at once.
+pub async fn get_exercise_sets_filtered<'local, E>(
+    conn: E,
+    user_id: i64,
+    filters: &ExerciseSetFilters,
+) -> Result<Vec<ExerciseSetEntity>>
+where
+    E: SqliteExecutor<'local>,
+{
+    let mut query = sqlx::QueryBuilder::<sqlx::Sqlite>::new(
+        "
+        SELECT
+            es.id, es.exercise_id, e.name AS exercise_name,
+            es.workout_id, es.created_utc_s, es.repetitions, es.weight, es.weight_unit, es.note
+        FROM exercise_set es
+        JOIN exercise e ON es.exercise_id = e.id
+        WHERE es.user_id =
+        ",
+    );
+    query.push_bind(user_id);
+    query.push(" AND es.deleted_utc_s IS NULL AND e.deleted_utc_s IS NULL");
+
+    if let Some(exercise_id) = filters.exercise_id {
+        query.push(" AND es.exercise_id = ").push_bind(exercise_id);
+    }
+    if let Some(workout_id) = filters.workout_id {
+        query.push(" AND es.workout_id = ").push_bind(workout_id);
+    }
+    if let Some(after) = filters.after {
+        query.push(" AND es.created_utc_s >= ").push_bind(after.timestamp());
+    }
+    if let Some(before) = filters.before {
+        query.push(" AND es.created_utc_s <= ").push_bind(before.timestamp());
+    }
+    if let Some(min_weight) = filters.min_weight {
+        query.push(" AND es.weight >= ").push_bind(min_weight);
+    }
+    if let Some(max_weight) = filters.max_weight {
+        query.push(" AND es.weight <= ").push_bind(max_weight);
+    }
+    if let Some(min_repetitions) = filters.min_repetitions {
+        query.push(" AND es.repetitions >= ").push_bind(min_repetitions);
+    }
+    if let Some(max_repetitions) = filters.max_repetitions {
+        query.push(" AND es.repetitions <= ").push_bind(max_repetitions);
+    }
+    if let Some(note_contains) = &filters.note_contains {
+        query.push(" AND es.note LIKE ").push_bind(format!("%{note_contains}%"));
+    }
+
+    query.push(" ORDER BY es.created_utc_s ");
+    query.push(if filters.reverse { "DESC" } else { "ASC" });
+
+    if let Some(limit) = filters.limit {
+        query.push(" LIMIT ").push_bind(limit);
+    }
+    if let Some(offset) = filters.offset {
+        query.push(" OFFSET ").push_bind(offset);
+    }
+
+    query
+        .build_query_as::<ExerciseSetEntity>()
+        .fetch_all(conn)
+        .await
+        .context("Failed to get filtered exercise sets")
 }
 
 pub async fn create_or_update_exercise_set<'local, E>(
     conn: E,
+    user_id: i64,
     exercise_set_id: Option<i64>,
     workout_id: i64,
     exercise_id: i64,
     repetitions: i64,
     weight: i64,
+    weight_unit: WeightUnit,
     note: String,
 ) -> Result<ExerciseSetEntity>
 where
@@ -294,17 +636,18 @@ where
         Some(_) => {
             "
             UPDATE exercise_set
-            SET workout_id = ?, exercise_id = ?, repetitions = ?, weight = ?, note = ?
-            WHERE id = ?
-            RETURNING id, exercise_id, workout_id, created_utc_s, repetitions, weight, note,
+            SET workout_id = ?, exercise_id = ?, repetitions = ?, weight = ?, weight_unit = ?, note = ?
+            WHERE user_id = ? AND id = ? AND deleted_utc_s IS NULL
+            RETURNING id, exercise_id, workout_id, created_utc_s, repetitions, weight, weight_unit, note,
                 '' AS exercise_name
             "
         }
         None => {
             "
-            INSERT INTO exercise_set (workout_id, exercise_id, repetitions, weight, note, created_utc_s)
-            VALUES (?, ?, ?, ?, ?, UNIXEPOCH(datetime()))
-            RETURNING id, exercise_id, workout_id, created_utc_s, repetitions, weight, note,
+            INSERT INTO exercise_set
+                (user_id, workout_id, exercise_id, repetitions, weight, weight_unit, note, created_utc_s)
+            VALUES (?, ?, ?, ?, ?, ?, ?, UNIXEPOCH(datetime()))
+            RETURNING id, exercise_id, workout_id, created_utc_s, repetitions, weight, weight_unit, note,
                 '' AS exercise_name
             "
         }
@@ -316,16 +659,27 @@ where
         note => Some(note),
     };
 
-    let mut query = sqlx::query_as::<_, ExerciseSetEntity>(query)
-        .bind(workout_id)
-        .bind(exercise_id)
-        .bind(repetitions)
-        .bind(weight)
-        .bind(note);
+    let mut query = sqlx::query_as::<_, ExerciseSetEntity>(query);
 
-    if let Some(id) = exercise_set_id {
-        query = query.bind(id);
-    }
+    query = match exercise_set_id {
+        Some(id) => query
+            .bind(workout_id)
+            .bind(exercise_id)
+            .bind(repetitions)
+            .bind(weight)
+            .bind(weight_unit)
+            .bind(note)
+            .bind(user_id)
+            .bind(id),
+        None => query
+            .bind(user_id)
+            .bind(workout_id)
+            .bind(exercise_id)
+            .bind(repetitions)
+            .bind(weight)
+            .bind(weight_unit)
+            .bind(note),
+    };
 
     let mut exercise_set = query
         .fetch_one(conn)
@@ -334,124 +688,250 @@ where
             format!("Failed to create exercise set with workout id {workout_id} and exercise id {exercise_id}")
         })?;
 
-    exercise_set.exercise_name = get_exercise(conn, exercise_id)
+    exercise_set.exercise_name = get_exercise(conn, user_id, exercise_id)
         .await?
-        .expect("Exercise must exist as it is used as a foreign key in the previous query")
+        .ok_or_else(|| anyhow::anyhow!("Exercise {exercise_id} was not found for user {user_id}"))?
         .name;
 
     Ok(exercise_set)
 }
 
-pub async fn delete_exercise_set<'local, E>(conn: E, id: i64) -> Result<Option<()>>
+pub async fn delete_exercise_set<'local, E>(conn: E, user_id: i64, id: i64) -> Result<Option<()>>
 where
     E: SqliteExecutor<'local>,
 {
-    sqlx::query("DELETE FROM exercise_set WHERE id = ?")
-        .bind(id)
-        .execute(conn)
-        .await
-        .map(|res| (res.rows_affected() > 0).then_some(()))
-        .with_context(|| format!("Failed to delete exercise set with id {id}"))
+    sqlx::query(
+        "
+        UPDATE exercise_set
+        SET deleted_utc_s = UNIXEPOCH(datetime())
+        WHERE user_id = ? AND id = ? AND deleted_utc_s IS NULL
+        ",
+    )
+    .bind(user_id)
+    .bind(id)
+    .execute(conn)
+    .await
+    .map(|res| (res.rows_affected() > 0).then_some(()))
+    .with_context(|| format!("Failed to delete exercise set with id {id}"))
+}
+
+pub async fn restore_exercise_set<'local, E>(
+    conn: E,
+    user_id: i64,
+    id: i64,
+) -> Result<Option<ExerciseSetEntity>>
+where
+    E: SqliteExecutor<'local> + Copy,
+{
+    let restored: Option<ExerciseSetEntity> = sqlx::query_as(
+        "
+        UPDATE exercise_set
+        SET deleted_utc_s = NULL
+        WHERE user_id = ? AND id = ? AND deleted_utc_s IS NOT NULL
+        RETURNING id, exercise_id, workout_id, created_utc_s, repetitions, weight, weight_unit, note,
+            '' AS exercise_name
+        ",
+    )
+    .bind(user_id)
+    .bind(id)
+    .fetch_optional(conn)
+    .await
+    .with_context(|| format!("Failed to restore exercise set with id {id}"))?;
+
+    let Some(mut restored) = restored else {
+        return Ok(None);
+    };
+
+    restored.exercise_name = get_exercise(conn, user_id, restored.exercise_id)
+        .await?
+        .map(|exercise| exercise.name)
+        .unwrap_or_default();
+
+    Ok(Some(restored))
 }
 
 pub async fn get_set_suggestion_for_workout<'local, E>(
     conn: E,
+    user_id: i64,
     workout_id: i64,
     exercise_id: Option<i64>,
+    preferred_unit: WeightUnit,
 ) -> Result<SetSuggestionEntity>
 where
     E: SqliteExecutor<'local> + Copy,
 {
     let suggest_with_exercise_id = |exercise_id: i64| async move {
-        // Suggest the last set of the same exercise in the same workout.
-        let suggestion = sqlx::query_as::<_, SetSuggestionEntity>(
+        // If this exercise has already been logged in the current workout,
+        // just suggest repeating that set.
+        let current_workout_set: Option<(i64, i64, WeightUnit)> = sqlx::query_as(
             "
-            SELECT exercise_id, repetitions, weight
-            FROM exercise_set
-            WHERE workout_id = ?
-                AND exercise_id = ?
-            ORDER BY created_utc_s DESC
+            SELECT es.repetitions, es.weight, es.weight_unit
+            FROM exercise_set es
+            JOIN exercise e ON es.exercise_id = e.id
+            WHERE es.user_id = ?
+                AND es.exercise_id = ?
+                AND es.workout_id = ?
+                AND es.deleted_utc_s IS NULL
+                AND e.deleted_utc_s IS NULL
+            ORDER BY es.created_utc_s DESC
             LIMIT 1
             ",
         )
-        .bind(workout_id)
+        .bind(user_id)
         .bind(exercise_id)
+        .bind(workout_id)
         .fetch_optional(conn)
         .await?;
 
-        if let Some(set) = suggestion {
-            return Ok(set);
+        if let Some((repetitions, weight, unit)) = current_workout_set {
+            let weight_kg = unit.to_kg(weight);
+            return Ok(SetSuggestionEntity {
+                exercise_id,
+                repetitions,
+                weight: preferred_unit.from_kg(weight_kg),
+                weight_unit: preferred_unit,
+                e1rm: preferred_unit.from_kg_f64(estimate_one_rep_max(weight_kg, repetitions)),
+            });
         }
 
-        // Suggest the first set of the same exercise in the most recent workout
-        // that contains this exercise.
-        let suggestion = sqlx::query_as::<_, SetSuggestionEntity>(
+        // Otherwise base the suggestion on a short progression trend: the
+        // last few sets logged for this exercise, regardless of workout.
+        let recent_sets: Vec<(i64, i64, WeightUnit)> = sqlx::query_as(
             "
-            SELECT exercise_id, repetitions, weight
-            FROM exercise_set
-            WHERE exercise_id = ?
-                AND workout_id = (
-                SELECT w.id
-                FROM workout w
-                JOIN exercise_set es ON w.id = es.workout_id
-                WHERE es.exercise_id = ?
-                ORDER BY started_utc_s DESC
-                LIMIT 1
-            )
-            ORDER BY created_utc_s
-            LIMIT 1
+            SELECT es.repetitions, es.weight, es.weight_unit
+            FROM exercise_set es
+            JOIN exercise e ON es.exercise_id = e.id
+            WHERE es.user_id = ?
+                AND es.exercise_id = ?
+                AND es.deleted_utc_s IS NULL
+                AND e.deleted_utc_s IS NULL
+            ORDER BY es.created_utc_s DESC
+            LIMIT ?
             ",
         )
+        .bind(user_id)
         .bind(exercise_id)
-        .bind(exercise_id)
-        .fetch_optional(conn)
+        .bind(PROGRESSION_LOOKBACK_SETS)
+        .fetch_all(conn)
         .await?;
 
-        Ok(suggestion.unwrap_or(SetSuggestionEntity {
+        let Some(&(last_repetitions, last_weight, last_unit)) = recent_sets.first() else {
+            // No history at all for this exercise.
+            return Ok(SetSuggestionEntity {
+                exercise_id,
+                repetitions: 0,
+                weight: 0,
+                weight_unit: preferred_unit,
+                e1rm: 0.0,
+            });
+        };
+
+        // Weights may have been logged in different units across sets, so
+        // normalize to kilograms before comparing or averaging them.
+        let last_weight_kg = last_unit.to_kg(last_weight);
+
+        let best_e1rm_kg = recent_sets
+            .iter()
+            .map(|&(reps, weight, unit)| estimate_one_rep_max(unit.to_kg(weight), reps))
+            .fold(0.0_f64, f64::max);
+
+        // The most recent set's rep count is the target the lifter has been
+        // working towards; did they consistently hit or exceed it, or fall
+        // short of it, over their last few sets?
+        let target_repetitions = last_repetitions as f64;
+        let avg_repetitions =
+            recent_sets.iter().map(|&(reps, _, _)| reps as f64).sum::<f64>() / recent_sets.len() as f64;
+
+        let (repetitions, weight) = if avg_repetitions >= target_repetitions {
+            // Consistently hit or exceeded the target: time to add weight.
+            let last_weight_in_preferred = preferred_unit.from_kg_f64(last_weight_kg);
+            (
+                last_repetitions,
+                round_to_plate_increment(
+                    last_weight_in_preferred * (1.0 + PROGRESSION_FACTOR),
+                    preferred_unit.plate_increment(),
+                ),
+            )
+        } else if avg_repetitions >= target_repetitions - 1.0 {
+            // Roughly on target: repeat the same weight and rep target.
+            (last_repetitions, preferred_unit.from_kg(last_weight_kg))
+        } else {
+            // Missed the target repeatedly: keep the weight, ease off the
+            // rep target instead.
+            ((last_repetitions - 1).max(1), preferred_unit.from_kg(last_weight_kg))
+        };
+
+        Ok(SetSuggestionEntity {
             exercise_id,
-            repetitions: 0,
-            weight: 0,
-        }))
+            repetitions,
+            weight,
+            weight_unit: preferred_unit,
+            e1rm: preferred_unit.from_kg_f64(best_e1rm_kg),
+        })
     };
 
     let suggest_without_exercise_id = || async {
         // Just suggest the last set again.
-        let suggestion = sqlx::query_as::<_, SetSuggestionEntity>(
+        let suggestion: Option<(i64, i64, i64, WeightUnit)> = sqlx::query_as(
             "
-            SELECT exercise_id, repetitions, weight
+            SELECT exercise_id, repetitions, weight, weight_unit
             FROM exercise_set
-            WHERE workout_id = ?
+            WHERE user_id = ?
+                AND workout_id = ?
+                AND deleted_utc_s IS NULL
             ORDER BY created_utc_s DESC
             LIMIT 1
             ",
         )
+        .bind(user_id)
         .bind(workout_id)
         .fetch_optional(conn)
         .await?;
 
-        if let Some(set) = suggestion {
-            return Ok(set);
+        if let Some((exercise_id, repetitions, weight, unit)) = suggestion {
+            let weight_kg = unit.to_kg(weight);
+            return Ok(SetSuggestionEntity {
+                exercise_id,
+                repetitions,
+                weight: preferred_unit.from_kg(weight_kg),
+                weight_unit: preferred_unit,
+                e1rm: preferred_unit.from_kg_f64(estimate_one_rep_max(weight_kg, repetitions)),
+            });
         }
 
         // Suggest the first set of the last workout that contains sets.
-        let suggestion = sqlx::query_as::<_, SetSuggestionEntity>(
+        let suggestion: Option<(i64, i64, i64, WeightUnit)> = sqlx::query_as(
             "
-            SELECT exercise_id, repetitions, weight
+            SELECT exercise_id, repetitions, weight, weight_unit
             FROM exercise_set
-            WHERE workout_id = (
+            WHERE user_id = ?
+                AND deleted_utc_s IS NULL
+                AND workout_id = (
                 SELECT MAX(w.id)
                 FROM workout w
                 JOIN exercise_set es ON w.id = es.workout_id
+                WHERE w.user_id = ?
+                    AND w.deleted_utc_s IS NULL
+                    AND es.deleted_utc_s IS NULL
             )
             ORDER BY created_utc_s
             LIMIT 1
             ",
         )
+        .bind(user_id)
+        .bind(user_id)
         .fetch_optional(conn)
         .await?;
 
-        if let Some(set) = suggestion {
-            return Ok(set);
+        if let Some((exercise_id, repetitions, weight, unit)) = suggestion {
+            let weight_kg = unit.to_kg(weight);
+            return Ok(SetSuggestionEntity {
+                exercise_id,
+                repetitions,
+                weight: preferred_unit.from_kg(weight_kg),
+                weight_unit: preferred_unit,
+                e1rm: preferred_unit.from_kg_f64(estimate_one_rep_max(weight_kg, repetitions)),
+            });
         }
 
         // Just return some sane defaults.
@@ -459,6 +939,8 @@ where
             exercise_id: 0,
             repetitions: 0,
             weight: 0,
+            weight_unit: preferred_unit,
+            e1rm: 0.0,
         })
     };
 
@@ -468,7 +950,120 @@ where
     }
 }
 
-pub async fn get_statistics_overview<'local, E>(conn: E) -> Result<StatisticsOverviewEntity>
+/// A single point of an exercise's estimated 1RM progression, i.e. the best
+/// estimated 1RM achieved in one workout.
+#[derive(Debug, FromRow)]
+pub struct ExerciseProgressionPointEntity {
+    pub workout_id: i64,
+    #[sqlx(rename = "started_utc_s")]
+    pub started: DateTime<Utc>,
+    pub e1rm: f64,
+}
+
+#[derive(Debug)]
+pub struct ExerciseRecordsEntity {
+    pub exercise_id: i64,
+    pub heaviest_weight: i64,
+    pub weight_unit: WeightUnit,
+    pub highest_repetitions: i64,
+    pub best_e1rm: f64,
+    pub progression: Vec<ExerciseProgressionPointEntity>,
+}
+
+/// Returns personal-record style statistics for a single exercise: the
+/// heaviest single set, the highest rep count, the best estimated one-rep-max
+/// ever achieved, and a per-workout time series of estimated 1RM suitable for
+/// charting progression over time.
+pub async fn get_exercise_records<'local, E>(
+    conn: E,
+    user_id: i64,
+    exercise_id: i64,
+    preferred_unit: WeightUnit,
+) -> Result<ExerciseRecordsEntity>
+where
+    E: SqliteExecutor<'local>,
+{
+    #[derive(Debug, FromRow)]
+    struct SetRow {
+        workout_id: i64,
+        #[sqlx(rename = "started_utc_s")]
+        started: DateTime<Utc>,
+        repetitions: i64,
+        weight: i64,
+        weight_unit: WeightUnit,
+    }
+
+    let rows: Vec<SetRow> = sqlx::query_as(
+        "
+        SELECT es.workout_id, w.started_utc_s, es.repetitions, es.weight, es.weight_unit
+        FROM exercise_set es
+        JOIN workout w ON es.workout_id = w.id
+        JOIN exercise e ON es.exercise_id = e.id
+        WHERE es.user_id = ?
+            AND es.exercise_id = ?
+            AND es.deleted_utc_s IS NULL
+            AND w.deleted_utc_s IS NULL
+            AND e.deleted_utc_s IS NULL
+        ORDER BY w.started_utc_s
+        ",
+    )
+    .bind(user_id)
+    .bind(exercise_id)
+    .fetch_all(conn)
+    .await
+    .with_context(|| format!("Failed to get exercise records for exercise with id {exercise_id}"))?;
+
+    if rows.is_empty() {
+        return Ok(ExerciseRecordsEntity {
+            exercise_id,
+            heaviest_weight: 0,
+            weight_unit: preferred_unit,
+            highest_repetitions: 0,
+            best_e1rm: 0.0,
+            progression: Vec::new(),
+        });
+    }
+
+    let mut heaviest_weight_kg = 0.0_f64;
+    let mut highest_repetitions = 0;
+    let mut best_e1rm_kg = 0.0_f64;
+    let mut progression: Vec<ExerciseProgressionPointEntity> = Vec::new();
+
+    for row in &rows {
+        let weight_kg = row.weight_unit.to_kg(row.weight);
+        heaviest_weight_kg = heaviest_weight_kg.max(weight_kg);
+        highest_repetitions = highest_repetitions.max(row.repetitions);
+
+        let e1rm_kg = epley_one_rep_max(weight_kg, row.repetitions);
+        best_e1rm_kg = best_e1rm_kg.max(e1rm_kg);
+
+        let e1rm = preferred_unit.from_kg_f64(e1rm_kg);
+        match progression.last_mut() {
+            Some(point) if point.workout_id == row.workout_id => {
+                point.e1rm = point.e1rm.max(e1rm);
+            }
+            _ => progression.push(ExerciseProgressionPointEntity {
+                workout_id: row.workout_id,
+                started: row.started,
+                e1rm,
+            }),
+        }
+    }
+
+    Ok(ExerciseRecordsEntity {
+        exercise_id,
+        heaviest_weight: preferred_unit.from_kg(heaviest_weight_kg),
+        weight_unit: preferred_unit,
+        highest_repetitions,
+        best_e1rm: preferred_unit.from_kg_f64(best_e1rm_kg),
+        progression,
+    })
+}
+
+pub async fn get_statistics_overview<'local, E>(
+    conn: E,
+    user_id: i64,
+) -> Result<StatisticsOverviewEntity>
 where
     E: SqliteExecutor<'local> + Copy,
 {
@@ -483,9 +1078,15 @@ where
         SELECT w.started_utc_s AS start_utc_s, MAX(es.created_utc_s) AS end_utc_s
         FROM exercise_set es
         JOIN workout w on es.workout_id = w.id
+        JOIN exercise e ON es.exercise_id = e.id
+        WHERE w.user_id = ?
+            AND w.deleted_utc_s IS NULL
+            AND es.deleted_utc_s IS NULL
+            AND e.deleted_utc_s IS NULL
         GROUP BY w.id
         ",
     )
+    .bind(user_id)
     .fetch_all(conn)
     .await?;
 
@@ -511,12 +1112,17 @@ where
     let sets_reps = sqlx::query_as::<_, SetsRepsRow>(
         "
         SELECT
-            COUNT(id) AS total_sets,
-            SUM(repetitions) AS total_repetitions,
-            CAST(AVG(repetitions) AS INT) AS avg_repetitions_per_set
-        FROM exercise_set
+            COUNT(es.id) AS total_sets,
+            SUM(es.repetitions) AS total_repetitions,
+            CAST(AVG(es.repetitions) AS INT) AS avg_repetitions_per_set
+        FROM exercise_set es
+        JOIN exercise e ON es.exercise_id = e.id
+        WHERE es.user_id = ?
+            AND es.deleted_utc_s IS NULL
+            AND e.deleted_utc_s IS NULL
         ",
     )
+    .bind(user_id)
     .fetch_one(conn)
     .await?;
 
@@ -526,3 +1132,654 @@ where
 
     Ok(overview)
 }
+
+#[derive(Debug, FromRow)]
+pub struct JobEntity {
+    pub id: i64,
+    pub kind: String,
+    pub payload: String,
+}
+
+#[derive(Debug, FromRow)]
+pub struct StatisticsCacheEntity {
+    pub total_workouts: i64,
+    pub total_duration_s: i64,
+    pub avg_duration_s: i64,
+    pub total_sets: i64,
+    pub total_repetitions: i64,
+    pub avg_repetitions_per_set: i64,
+    #[sqlx(rename = "computed_utc_s")]
+    pub computed: DateTime<Utc>,
+}
+
+/// Kind of [`JobEntity`] that recomputes and caches a user's statistics overview.
+pub const JOB_KIND_RECOMPUTE_STATISTICS: &str = "recompute_statistics";
+
+/// Queues a job that recomputes and caches the statistics overview for `user_id`.
+pub async fn enqueue_recompute_statistics_job<'local, E>(conn: E, user_id: i64) -> Result<()>
+where
+    E: SqliteExecutor<'local>,
+{
+    let payload = format!(r#"{{"user_id":{user_id}}}"#);
+
+    sqlx::query("INSERT INTO jobs (kind, payload) VALUES (?, ?)")
+        .bind(JOB_KIND_RECOMPUTE_STATISTICS)
+        .bind(payload)
+        .execute(conn)
+        .await
+        .with_context(|| format!("Failed to enqueue statistics recompute job for user {user_id}"))?;
+
+    Ok(())
+}
+
+/// Atomically claims the oldest `new` job and marks it as `running`, so that
+/// multiple workers can pull from the same queue without processing the same
+/// job twice.
+pub async fn claim_next_job<'local, E>(conn: E) -> Result<Option<JobEntity>>
+where
+    E: SqliteExecutor<'local>,
+{
+    sqlx::query_as(
+        "
+        UPDATE jobs
+        SET status = 'running', updated_utc_s = UNIXEPOCH(datetime())
+        WHERE id = (SELECT id FROM jobs WHERE status = 'new' ORDER BY id LIMIT 1)
+        RETURNING id, kind, payload
+        ",
+    )
+    .fetch_optional(conn)
+    .await
+    .context("Failed to claim next job")
+}
+
+pub async fn complete_job<'local, E>(conn: E, id: i64) -> Result<()>
+where
+    E: SqliteExecutor<'local>,
+{
+    sqlx::query("UPDATE jobs SET status = 'done', updated_utc_s = UNIXEPOCH(datetime()) WHERE id = ?")
+        .bind(id)
+        .execute(conn)
+        .await
+        .with_context(|| format!("Failed to mark job {id} as done"))?;
+
+    Ok(())
+}
+
+/// Overwrites the cached statistics overview for `user_id` with a freshly
+/// computed `overview`.
+pub async fn cache_statistics_overview<'local, E>(
+    conn: E,
+    user_id: i64,
+    overview: &StatisticsOverviewEntity,
+) -> Result<()>
+where
+    E: SqliteExecutor<'local>,
+{
+    sqlx::query(
+        "
+        INSERT INTO statistics_cache (
+            user_id, total_workouts, total_duration_s, avg_duration_s,
+            total_sets, total_repetitions, avg_repetitions_per_set, computed_utc_s
+        )
+        VALUES (?, ?, ?, ?, ?, ?, ?, UNIXEPOCH(datetime()))
+        ON CONFLICT (user_id) DO UPDATE SET
+            total_workouts = excluded.total_workouts,
+            total_duration_s = excluded.total_duration_s,
+            avg_duration_s = excluded.avg_duration_s,
+            total_sets = excluded.total_sets,
+            total_repetitions = excluded.total_repetitions,
+            avg_repetitions_per_set = excluded.avg_repetitions_per_set,
+            computed_utc_s = excluded.computed_utc_s
+        ",
+    )
+    .bind(user_id)
+    .bind(overview.total_workouts)
+    .bind(overview.total_duration_s)
+    .bind(overview.avg_duration_s)
+    .bind(overview.total_sets)
+    .bind(overview.total_repetitions)
+    .bind(overview.avg_repetitions_per_set)
+    .execute(conn)
+    .await
+    .with_context(|| format!("Failed to cache statistics overview for user {user_id}"))?;
+
+    Ok(())
+}
+
+pub async fn get_cached_statistics_overview<'local, E>(
+    conn: E,
+    user_id: i64,
+) -> Result<Option<StatisticsCacheEntity>>
+where
+    E: SqliteExecutor<'local>,
+{
+    sqlx::query_as(
+        "
+        SELECT
+            total_workouts, total_duration_s, avg_duration_s,
+            total_sets, total_repetitions, avg_repetitions_per_set, computed_utc_s
+        FROM statistics_cache
+        WHERE user_id = ?
+        ",
+    )
+    .bind(user_id)
+    .fetch_optional(conn)
+    .await
+    .with_context(|| format!("Failed to get cached statistics overview for user {user_id}"))
+}
+
+/// Permanently removes exercises, workouts, and exercise sets that were
+/// soft-deleted before `older_than`, clearing out the trash.
+pub async fn purge_deleted<'local, E>(conn: E, older_than: DateTime<Utc>) -> Result<()>
+where
+    E: SqliteExecutor<'local> + Copy,
+{
+    let cutoff = older_than.timestamp();
+
+    // Children first so a concurrent restore can't leave a set pointing at an
+    // already-purged exercise or workout. A set is purged either because it
+    // is itself past retention, or because the exercise/workout it belongs to
+    // is about to be purged below — deleting an exercise or workout never
+    // cascades to its sets, so this is the only place that catches them.
+    sqlx::query(
+        "
+        DELETE FROM exercise_set
+        WHERE (deleted_utc_s IS NOT NULL AND deleted_utc_s < ?)
+            OR workout_id IN (SELECT id FROM workout WHERE deleted_utc_s IS NOT NULL AND deleted_utc_s < ?)
+            OR exercise_id IN (SELECT id FROM exercise WHERE deleted_utc_s IS NOT NULL AND deleted_utc_s < ?)
+        ",
+    )
+    .bind(cutoff)
+    .bind(cutoff)
+    .bind(cutoff)
+    .execute(conn)
+    .await
+    .context("Failed to purge deleted exercise sets")?;
+
+    sqlx::query("DELETE FROM workout WHERE deleted_utc_s IS NOT NULL AND deleted_utc_s < ?")
+        .bind(cutoff)
+        .execute(conn)
+        .await
+        .context("Failed to purge deleted workouts")?;
+
+    sqlx::query("DELETE FROM exercise WHERE deleted_utc_s IS NOT NULL AND deleted_utc_s < ?")
+        .bind(cutoff)
+        .execute(conn)
+        .await
+        .context("Failed to purge deleted exercises")?;
+
+    Ok(())
+}
+
+/// Kind of [`MeasurementEntity`] tracking bodyweight, used to correlate body
+/// weight trend against training volume in [`get_bodyweight_vs_training_volume`].
+pub const MEASUREMENT_KIND_BODYWEIGHT: &str = "bodyweight";
+
+/// A single time-stamped body metric, e.g. bodyweight, body-fat percentage,
+/// or an arbitrary named measurement such as waist or arm circumference.
+#[derive(Debug, FromRow)]
+pub struct MeasurementEntity {
+    pub id: i64,
+    pub kind: String,
+    pub value: f64,
+    #[sqlx(rename = "recorded_utc_s")]
+    pub recorded: DateTime<Utc>,
+}
+
+pub async fn create_measurement<'local, E>(
+    conn: E,
+    user_id: i64,
+    kind: &str,
+    value: f64,
+) -> Result<MeasurementEntity>
+where
+    E: SqliteExecutor<'local>,
+{
+    sqlx::query_as(
+        "
+        INSERT INTO measurements (user_id, kind, value, recorded_utc_s)
+        VALUES (?, ?, ?, UNIXEPOCH(datetime()))
+        RETURNING id, kind, value, recorded_utc_s
+        ",
+    )
+    .bind(user_id)
+    .bind(kind)
+    .bind(value)
+    .fetch_one(conn)
+    .await
+    .with_context(|| format!(r#"Failed to create "{kind}" measurement"#))
+}
+
+/// Returns measurements logged for `user_id`, optionally narrowed to a single
+/// `kind` and/or a `[after, before]` time range, newest first.
+pub async fn get_measurements<'local, E>(
+    conn: E,
+    user_id: i64,
+    kind: Option<&str>,
+    after: Option<DateTime<Utc>>,
+    before: Option<DateTime<Utc>>,
+) -> Result<Vec<MeasurementEntity>>
+where
+    E: SqliteExecutor<'local>,
+{
+    let mut query = sqlx::QueryBuilder::<sqlx::Sqlite>::new(
+        "SELECT id, kind, value, recorded_utc_s FROM measurements WHERE user_id = ",
+    );
+    query.push_bind(user_id);
+
+    if let Some(kind) = kind {
+        query.push(" AND kind = ").push_bind(kind.to_owned());
+    }
+    if let Some(after) = after {
+        query.push(" AND recorded_utc_s >= ").push_bind(after.timestamp());
+    }
+    if let Some(before) = before {
+        query.push(" AND recorded_utc_s <= ").push_bind(before.timestamp());
+    }
+
+    query.push(" ORDER BY recorded_utc_s DESC");
+
+    query
+        .build_query_as::<MeasurementEntity>()
+        .fetch_all(conn)
+        .await
+        .context("Failed to get measurements")
+}
+
+pub async fn update_measurement<'local, E>(
+    conn: E,
+    user_id: i64,
+    id: i64,
+    value: f64,
+) -> Result<Option<MeasurementEntity>>
+where
+    E: SqliteExecutor<'local>,
+{
+    sqlx::query_as(
+        "
+        UPDATE measurements SET value = ?
+        WHERE user_id = ? AND id = ?
+        RETURNING id, kind, value, recorded_utc_s
+        ",
+    )
+    .bind(value)
+    .bind(user_id)
+    .bind(id)
+    .fetch_optional(conn)
+    .await
+    .with_context(|| format!("Failed to update measurement with id {id}"))
+}
+
+pub async fn delete_measurement<'local, E>(conn: E, user_id: i64, id: i64) -> Result<Option<()>>
+where
+    E: SqliteExecutor<'local>,
+{
+    sqlx::query("DELETE FROM measurements WHERE user_id = ? AND id = ?")
+        .bind(user_id)
+        .bind(id)
+        .execute(conn)
+        .await
+        .map(|res| (res.rows_affected() > 0).then_some(()))
+        .with_context(|| format!("Failed to delete measurement with id {id}"))
+}
+
+/// One point in the correlation between bodyweight and training volume: the
+/// most recent bodyweight measurement known by the time of a workout, and
+/// that workout's total training volume (weight times repetitions across its
+/// sets, normalized to kilograms since sets may be logged in different units).
+#[derive(Debug)]
+pub struct BodyweightVolumePointEntity {
+    pub workout_id: i64,
+    pub started: DateTime<Utc>,
+    pub bodyweight: Option<f64>,
+    pub volume_kg: f64,
+}
+
+/// Correlates bodyweight trend against training volume over the same period,
+/// one point per workout, ordered oldest first.
+pub async fn get_bodyweight_vs_training_volume<'local, E>(
+    conn: E,
+    user_id: i64,
+) -> Result<Vec<BodyweightVolumePointEntity>>
+where
+    E: SqliteExecutor<'local> + Copy,
+{
+    #[derive(Debug, FromRow)]
+    struct WorkoutRow {
+        id: i64,
+        #[sqlx(rename = "started_utc_s")]
+        started: DateTime<Utc>,
+    }
+
+    let workouts: Vec<WorkoutRow> = sqlx::query_as(
+        "
+        SELECT id, started_utc_s FROM workout
+        WHERE user_id = ? AND deleted_utc_s IS NULL
+        ORDER BY started_utc_s
+        ",
+    )
+    .bind(user_id)
+    .fetch_all(conn)
+    .await
+    .context("Failed to get workouts for bodyweight vs training volume correlation")?;
+
+    #[derive(Debug, FromRow)]
+    struct SetRow {
+        workout_id: i64,
+        weight: i64,
+        weight_unit: WeightUnit,
+        repetitions: i64,
+    }
+
+    let sets: Vec<SetRow> = sqlx::query_as(
+        "
+        SELECT es.workout_id, es.weight, es.weight_unit, es.repetitions
+        FROM exercise_set es
+        JOIN exercise e ON es.exercise_id = e.id
+        WHERE es.user_id = ? AND es.deleted_utc_s IS NULL AND e.deleted_utc_s IS NULL
+        ",
+    )
+    .bind(user_id)
+    .fetch_all(conn)
+    .await
+    .context("Failed to get exercise sets for bodyweight vs training volume correlation")?;
+
+    #[derive(Debug, FromRow)]
+    struct BodyweightRow {
+        value: f64,
+        #[sqlx(rename = "recorded_utc_s")]
+        recorded: DateTime<Utc>,
+    }
+
+    let bodyweights: Vec<BodyweightRow> = sqlx::query_as(
+        "
+        SELECT value, recorded_utc_s FROM measurements
+        WHERE user_id = ? AND kind = ?
+        ORDER BY recorded_utc_s
+        ",
+    )
+    .bind(user_id)
+    .bind(MEASUREMENT_KIND_BODYWEIGHT)
+    .fetch_all(conn)
+    .await
+    .context("Failed to get bodyweight measurements for training volume correlation")?;
+
+    let mut points = Vec::with_capacity(workouts.len());
+    let mut bodyweights = bodyweights.into_iter().peekable();
+    let mut last_bodyweight = None;
+
+    for workout in workouts {
+        let volume_kg = sets
+            .iter()
+            .filter(|set| set.workout_id == workout.id)
+            .map(|set| set.weight_unit.to_kg(set.weight) * set.repetitions as f64)
+            .sum();
+
+        // Both lists are sorted by time, so advance through the bodyweight
+        // measurements up to this workout's start, keeping the most recent one.
+        while let Some(next) = bodyweights.peek() {
+            if next.recorded > workout.started {
+                break;
+            }
+            last_bodyweight = Some(bodyweights.next().unwrap().value);
+        }
+
+        points.push(BodyweightVolumePointEntity {
+            workout_id: workout.id,
+            started: workout.started,
+            bodyweight: last_bodyweight,
+            volume_kg,
+        });
+    }
+
+    Ok(points)
+}
+
+/// Current shape of a [`BackupEntity`] document. Bumped whenever the
+/// document's shape changes incompatibly, so future imports can tell which
+/// migrations to run.
+pub const BACKUP_SCHEMA_VERSION: i32 = 1;
+
+/// A full, portable snapshot of a user's exercises, workouts, and exercise
+/// sets, suitable for backup or migration to another instance.
+#[derive(Debug)]
+pub struct BackupEntity {
+    pub schema_version: i32,
+    pub exported_at: DateTime<Utc>,
+    pub exercises: Vec<ExerciseEntity>,
+    pub workouts: Vec<WorkoutEntity>,
+    pub exercise_sets: Vec<ExerciseSetEntity>,
+}
+
+/// Exports all of `user_id`'s exercises, workouts, and exercise sets into a
+/// single portable document.
+pub async fn export_backup<'local, E>(conn: E, user_id: i64) -> Result<BackupEntity>
+where
+    E: SqliteExecutor<'local> + Copy,
+{
+    Ok(BackupEntity {
+        schema_version: BACKUP_SCHEMA_VERSION,
+        exported_at: Utc::now(),
+        exercises: get_exercises(conn, user_id).await?,
+        workouts: get_workouts(conn, user_id).await?,
+        exercise_sets: get_exercise_sets(conn, user_id).await?,
+    })
+}
+
+/// How many rows [`import_backup`] actually wrote versus left alone because
+/// they already existed.
+#[derive(Debug, Default)]
+pub struct ImportSummaryEntity {
+    pub exercises_imported: i64,
+    pub workouts_imported: i64,
+    pub sets_imported: i64,
+    pub sets_skipped: i64,
+}
+
+async fn get_exercise_by_name<'local, E>(
+    conn: E,
+    user_id: i64,
+    name: &str,
+) -> Result<Option<ExerciseEntity>>
+where
+    E: SqliteExecutor<'local>,
+{
+    sqlx::query_as(&format!(
+        "SELECT id, name, {HAS_IMAGE_EXPR} FROM exercise WHERE user_id = ? AND name = ? AND deleted_utc_s IS NULL"
+    ))
+    .bind(user_id)
+    .bind(name)
+    .fetch_optional(conn)
+    .await
+    .with_context(|| format!(r#"Failed to get exercise with name "{name}""#))
+}
+
+async fn get_workout_by_started<'local, E>(
+    conn: E,
+    user_id: i64,
+    started: DateTime<Utc>,
+) -> Result<Option<WorkoutEntity>>
+where
+    E: SqliteExecutor<'local>,
+{
+    sqlx::query_as(
+        "
+        SELECT id, started_utc_s, note FROM workout
+        WHERE user_id = ? AND started_utc_s = ? AND deleted_utc_s IS NULL
+        ",
+    )
+    .bind(user_id)
+    .bind(started.timestamp())
+    .fetch_optional(conn)
+    .await
+    .context("Failed to get workout by start time")
+}
+
+async fn create_workout_with_started<'local, E>(
+    conn: E,
+    user_id: i64,
+    started: DateTime<Utc>,
+    note: Option<&str>,
+) -> Result<WorkoutEntity>
+where
+    E: SqliteExecutor<'local>,
+{
+    sqlx::query_as(
+        "
+        INSERT INTO workout (user_id, started_utc_s, note) VALUES (?, ?, ?)
+        RETURNING id, started_utc_s, note
+        ",
+    )
+    .bind(user_id)
+    .bind(started.timestamp())
+    .bind(note)
+    .fetch_one(conn)
+    .await
+    .context("Failed to create workout during import")
+}
+
+async fn get_exercise_set_by_identity<'local, E>(
+    conn: E,
+    user_id: i64,
+    workout_id: i64,
+    exercise_id: i64,
+    created: DateTime<Utc>,
+) -> Result<Option<i64>>
+where
+    E: SqliteExecutor<'local>,
+{
+    sqlx::query_scalar(
+        "
+        SELECT id FROM exercise_set
+        WHERE user_id = ? AND workout_id = ? AND exercise_id = ? AND created_utc_s = ?
+            AND deleted_utc_s IS NULL
+        ",
+    )
+    .bind(user_id)
+    .bind(workout_id)
+    .bind(exercise_id)
+    .bind(created.timestamp())
+    .fetch_optional(conn)
+    .await
+    .context("Failed to check for an existing exercise set during import")
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn create_exercise_set_with_created<'local, E>(
+    conn: E,
+    user_id: i64,
+    workout_id: i64,
+    exercise_id: i64,
+    repetitions: i64,
+    weight: i64,
+    weight_unit: WeightUnit,
+    note: Option<&str>,
+    created: DateTime<Utc>,
+) -> Result<()>
+where
+    E: SqliteExecutor<'local>,
+{
+    sqlx::query(
+        "
+        INSERT INTO exercise_set
+            (user_id, workout_id, exercise_id, repetitions, weight, weight_unit, note, created_utc_s)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+        ",
+    )
+    .bind(user_id)
+    .bind(workout_id)
+    .bind(exercise_id)
+    .bind(repetitions)
+    .bind(weight)
+    .bind(weight_unit)
+    .bind(note)
+    .bind(created.timestamp())
+    .execute(conn)
+    .await
+    .context("Failed to create exercise set during import")?;
+
+    Ok(())
+}
+
+/// Imports a previously [exported](export_backup) backup for `user_id`,
+/// transactionally. Exercises are matched and reused by name, and workouts by
+/// their original start time, so ids embedded in the backup are never written
+/// directly and can't collide with a user's existing data. Re-importing the
+/// same backup is idempotent: a set already matching an existing set's
+/// workout, exercise, and creation time is skipped rather than duplicated.
+pub async fn import_backup(
+    pool: &sqlx::Pool<sqlx::Sqlite>,
+    user_id: i64,
+    backup: &BackupEntity,
+) -> Result<ImportSummaryEntity> {
+    let mut tx = pool.begin().await.context("Failed to begin import transaction")?;
+    let mut summary = ImportSummaryEntity::default();
+
+    let mut exercise_ids = std::collections::HashMap::new();
+    for exercise in &backup.exercises {
+        let id = match get_exercise_by_name(&mut *tx, user_id, &exercise.name).await? {
+            Some(existing) => existing.id,
+            None => {
+                let created = create_exercise(&mut *tx, user_id, &exercise.name).await?;
+                summary.exercises_imported += 1;
+                created.id
+            }
+        };
+        exercise_ids.insert(exercise.id, id);
+    }
+
+    let mut workout_ids = std::collections::HashMap::new();
+    for workout in &backup.workouts {
+        let id = match get_workout_by_started(&mut *tx, user_id, workout.started).await? {
+            Some(existing) => existing.id,
+            None => {
+                let created =
+                    create_workout_with_started(&mut *tx, user_id, workout.started, workout.note.as_deref())
+                        .await?;
+                summary.workouts_imported += 1;
+                created.id
+            }
+        };
+        workout_ids.insert(workout.id, id);
+    }
+
+    for set in &backup.exercise_sets {
+        let (Some(&exercise_id), Some(&workout_id)) =
+            (exercise_ids.get(&set.exercise_id), workout_ids.get(&set.workout_id))
+        else {
+            // The exercise or workout this set belonged to was itself absent
+            // from the backup; nothing sensible to attach it to.
+            continue;
+        };
+
+        if get_exercise_set_by_identity(&mut *tx, user_id, workout_id, exercise_id, set.created)
+            .await?
+            .is_some()
+        {
+            summary.sets_skipped += 1;
+            continue;
+        }
+
+        create_exercise_set_with_created(
+            &mut *tx,
+            user_id,
+            workout_id,
+            exercise_id,
+            set.repetitions,
+            set.weight,
+            set.weight_unit,
+            set.note.as_deref(),
+            set.created,
+        )
+        .await?;
+        summary.sets_imported += 1;
+    }
+
+    if summary.sets_imported > 0 {
+        enqueue_recompute_statistics_job(&mut *tx, user_id).await?;
+    }
+
+    tx.commit().await.context("Failed to commit import transaction")?;
+
+    Ok(summary)
+}
@@ -24,6 +24,22 @@ struct Args {
     /// address and port to listen on (default 127.0.0.1:8080)
     #[argh(option, default = "\"127.0.0.1:8080\".parse().unwrap()")]
     addr: SocketAddr,
+
+    /// secret used to sign authentication JWTs
+    #[argh(option, default = "\"change-me-in-production\".to_string()")]
+    jwt_secret: String,
+
+    /// origin allowed to make cross-origin requests to the API (repeatable)
+    #[argh(option)]
+    cors_origin: Vec<String>,
+
+    /// serve behind HTTPS (directly or via a TLS-terminating proxy). Required
+    /// for cross-origin requests to actually authenticate, since browsers
+    /// only honor `SameSite=None` cookies when they're also `Secure`; leave
+    /// unset for plain-HTTP local development, where the auth cookie falls
+    /// back to `SameSite=Lax` and cross-origin requests can't authenticate
+    #[argh(switch)]
+    https: bool,
 }
 
 #[tokio::main]
@@ -35,7 +51,7 @@ async fn main() {
 
     let pool = setup_database(&args.db).await.unwrap();
 
-    server::run(&args.addr, pool).await;
+    server::run(&args.addr, pool, args.jwt_secret, args.cors_origin, args.https).await;
 }
 
 fn setup_tracing() {
@@ -1,40 +1,105 @@
-use std::net::SocketAddr;
+use std::{convert::Infallible, net::SocketAddr, sync::Arc, time::Duration};
 
 use axum::{
-    extract::{Path, State},
-    http::{header::CONTENT_TYPE, Request, StatusCode, Uri},
+    extract::{DefaultBodyLimit, Multipart, Path, Query, State},
+    http::{header::CONTENT_TYPE, HeaderValue, Method, Request, StatusCode, Uri},
     middleware::{self, Next},
-    response::{IntoResponse, Response},
-    routing::{get, post},
-    Json, Router, Server, ServiceExt,
+    response::{
+        sse::{Event, KeepAlive},
+        IntoResponse, Response, Sse,
+    },
+    routing::{get, post, put},
+    Extension, Json, Router, Server, ServiceExt,
 };
+use futures_util::{Stream, StreamExt};
 use include_dir::{include_dir, Dir};
 use sqlx::{Pool, Sqlite};
-use tokio::signal;
+use tokio::{signal, sync::broadcast};
+use tokio_stream::wrappers::BroadcastStream;
 use tower::ServiceBuilder;
 use tower_http::{
+    compression::CompressionLayer,
+    cors::{AllowOrigin, CorsLayer},
     request_id::MakeRequestUuid,
     trace::{DefaultMakeSpan, TraceLayer},
     ServiceBuilderExt,
 };
-use tracing::{error, info};
+use tracing::{error, info, warn};
+use utoipa::OpenApi;
+use utoipa_swagger_ui::SwaggerUi;
 
 use crate::dal;
 
 use self::{
+    auth::AuthUser,
+    events::SetEvent,
+    openapi::ApiDoc,
     requests::{CreateUpdateExercise, CreateUpdateExerciseSet, GetSetSuggestion},
-    responses::{Exercise, ExerciseCount, ExerciseSet, SetSuggestion, StatisticsOverview, Workout},
+    responses::{
+        Backup, Exercise, ExerciseCount, ExerciseRecords, ExerciseSet, ImportSummary, SetSuggestion,
+        StatisticsOverview, Workout,
+    },
 };
 
 static STATIC_FILES: Dir<'_> = include_dir!("../client/dist");
 
+/// Number of events buffered per subscriber before old ones are dropped.
+const SET_EVENTS_CAPACITY: usize = 1024;
+
+/// Maximum accepted size of an uploaded exercise image, before re-encoding.
+const MAX_IMAGE_BYTES: usize = 8 * 1024 * 1024;
+
+/// Exercise images are downscaled to fit within this many pixels per side.
+const MAX_IMAGE_DIMENSION: u32 = 2048;
+
+/// Content type exercise images are normalized to when stored.
+const EXERCISE_IMAGE_CONTENT_TYPE: &str = "image/png";
+
+/// How long the job worker sleeps after finding an empty queue before
+/// polling again.
+const JOB_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// How long soft-deleted exercises, workouts, and sets are kept in the trash
+/// before being purged for good.
+const TRASH_RETENTION_DAYS: i64 = 30;
+
+/// How often the trash purge sweep runs.
+const PURGE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
 #[derive(Debug, Clone)]
 struct AppState {
     pool: Pool<Sqlite>,
+    jwt_secret: Arc<str>,
+    set_events: broadcast::Sender<SetEvent>,
+    /// Whether the auth cookie should be issued as `SameSite=None; Secure`
+    /// instead of the default `SameSite=Lax`. Browsers strip `Lax` cookies
+    /// from cross-site requests, so a decoupled frontend needs `None` to
+    /// actually authenticate through the credentialed CORS config below —
+    /// but `None` is only honored by browsers alongside `Secure`, so this is
+    /// only enabled when the caller confirms the server is reachable over
+    /// HTTPS (`https` is only meaningful together with a non-empty
+    /// `cors_origins`; same-origin deployments keep `Lax` either way).
+    cross_origin_cookies: bool,
 }
 
-pub async fn run(addr: &SocketAddr, pool: Pool<Sqlite>) {
-    let state = AppState { pool };
+pub async fn run(
+    addr: &SocketAddr,
+    pool: Pool<Sqlite>,
+    jwt_secret: String,
+    cors_origins: Vec<String>,
+    https: bool,
+) {
+    let state = AppState {
+        pool,
+        jwt_secret: Arc::from(jwt_secret),
+        set_events: broadcast::channel(SET_EVENTS_CAPACITY).0,
+        cross_origin_cookies: !cors_origins.is_empty() && https,
+    };
+
+    tokio::spawn(run_job_worker(state.pool.clone()));
+    tokio::spawn(run_trash_purge_worker(state.pool.clone()));
+
+    let require_auth_layer = || middleware::from_fn_with_state(state.clone(), auth::require_auth);
 
     let check_workout_exists_layer =
         || middleware::from_fn_with_state(state.clone(), check_workout_exists);
@@ -45,6 +110,10 @@ pub async fn run(addr: &SocketAddr, pool: Pool<Sqlite>) {
     let check_exercise_set_exists_layer =
         || middleware::from_fn_with_state(state.clone(), check_exercise_set_exists);
 
+    let auth_routes = Router::new()
+        .route("/auth/register", post(auth::register))
+        .route("/auth/login", post(auth::login));
+
     let endpoints = Router::new()
         .route("/workouts", get(get_workouts).post(create_workout))
         .route(
@@ -53,10 +122,15 @@ pub async fn run(addr: &SocketAddr, pool: Pool<Sqlite>) {
                 .delete(delete_workout)
                 .route_layer(check_workout_exists_layer()),
         )
+        .route("/workouts/:id/restore", post(restore_workout))
         .route(
             "/workouts/:id/sets",
             get(get_exercise_sets_by_workout_id).route_layer(check_workout_exists_layer()),
         )
+        .route(
+            "/workouts/:id/stream",
+            get(get_exercise_set_stream).route_layer(check_workout_exists_layer()),
+        )
         .route("/workouts/:id/sets/suggest", post(get_set_suggestion))
         .route("/exercises", get(get_exercises).post(create_exercise))
         .route(
@@ -66,15 +140,28 @@ pub async fn run(addr: &SocketAddr, pool: Pool<Sqlite>) {
                 .delete(delete_exercise)
                 .route_layer(check_exercise_exists_layer()),
         )
+        .route("/exercises/:id/restore", post(restore_exercise))
         .route(
             "/exercises/:id/sets",
             get(get_exercise_sets_by_exercise_id).route_layer(check_exercise_exists_layer()),
         )
+        .route(
+            "/exercises/:id/image",
+            post(upload_exercise_image)
+                .get(get_exercise_image)
+                .layer(DefaultBodyLimit::max(MAX_IMAGE_BYTES))
+                .route_layer(check_exercise_exists_layer()),
+        )
         .route(
             "/exercises/:id/count",
             get(get_exercise_count).route_layer(check_exercise_exists_layer()),
         )
+        .route(
+            "/exercises/:id/records",
+            get(get_exercise_records).route_layer(check_exercise_exists_layer()),
+        )
         .route("/sets", get(get_exercise_sets).post(create_exercise_set))
+        .route("/sets/search", get(search_exercise_sets))
         .route(
             "/sets/:id",
             get(get_exercise_set)
@@ -82,10 +169,23 @@ pub async fn run(addr: &SocketAddr, pool: Pool<Sqlite>) {
                 .delete(delete_exercise_set)
                 .route_layer(check_exercise_set_exists_layer()),
         )
-        .route("/statistics", get(get_statistics_overview));
+        .route("/sets/:id/restore", post(restore_exercise_set))
+        .route("/statistics", get(get_statistics_overview))
+        .route(
+            "/statistics/bodyweight-vs-volume",
+            get(get_bodyweight_vs_training_volume),
+        )
+        .route("/measurements", get(get_measurements).post(create_measurement))
+        .route(
+            "/measurements/:id",
+            put(update_measurement).delete(delete_measurement),
+        )
+        .route("/backup", get(export_backup).post(import_backup))
+        .route_layer(require_auth_layer());
 
     let router = Router::new()
-        .nest("/api", endpoints)
+        .nest("/api", Router::new().merge(auth_routes).merge(endpoints))
+        .merge(SwaggerUi::new("/api/docs").url("/api/openapi.json", ApiDoc::openapi()))
         .nest_service("/", get(get_static_file))
         .with_state(state);
 
@@ -96,6 +196,8 @@ pub async fn run(addr: &SocketAddr, pool: Pool<Sqlite>) {
                 .make_span_with(DefaultMakeSpan::default().include_headers(true)),
         )
         .propagate_x_request_id()
+        .layer(CompressionLayer::new().gzip(true))
+        .layer(build_cors_layer(&cors_origins))
         .service(router);
 
     info!(%addr, "Listening on {}", addr);
@@ -107,6 +209,36 @@ pub async fn run(addr: &SocketAddr, pool: Pool<Sqlite>) {
         .unwrap();
 }
 
+fn build_cors_layer(origins: &[String]) -> CorsLayer {
+    let layer = CorsLayer::new()
+        .allow_methods([
+            Method::GET,
+            Method::POST,
+            Method::PUT,
+            Method::DELETE,
+            Method::OPTIONS,
+        ])
+        .allow_headers([CONTENT_TYPE])
+        .allow_credentials(true);
+
+    if origins.is_empty() {
+        return layer;
+    }
+
+    let origins = origins
+        .iter()
+        .filter_map(|origin| match origin.parse::<HeaderValue>() {
+            Ok(origin) => Some(origin),
+            Err(err) => {
+                error!(%err, %origin, "Ignoring invalid CORS origin.");
+                None
+            }
+        })
+        .collect::<Vec<_>>();
+
+    layer.allow_origin(AllowOrigin::list(origins))
+}
+
 async fn shutdown_signal() {
     signal::ctrl_c()
         .await
@@ -115,6 +247,57 @@ async fn shutdown_signal() {
     info!("Shutting down...");
 }
 
+/// Background worker that polls the `jobs` table and processes queued work,
+/// currently just statistics recomputation, off the request path.
+async fn run_job_worker(pool: Pool<Sqlite>) {
+    loop {
+        match dal::claim_next_job(&pool).await {
+            Ok(Some(job)) => {
+                let id = job.id;
+                if let Err(err) = process_job(&pool, job).await {
+                    error!(%err, job.id = id, "Failed to process job.");
+                }
+            }
+            Ok(None) => tokio::time::sleep(JOB_POLL_INTERVAL).await,
+            Err(err) => {
+                error!(%err, "Failed to claim next job.");
+                tokio::time::sleep(JOB_POLL_INTERVAL).await;
+            }
+        }
+    }
+}
+
+async fn process_job(pool: &Pool<Sqlite>, job: dal::JobEntity) -> anyhow::Result<()> {
+    match job.kind.as_str() {
+        dal::JOB_KIND_RECOMPUTE_STATISTICS => {
+            #[derive(serde::Deserialize)]
+            struct Payload {
+                user_id: i64,
+            }
+
+            let payload: Payload = serde_json::from_str(&job.payload)?;
+            let overview = dal::get_statistics_overview(pool, payload.user_id).await?;
+            dal::cache_statistics_overview(pool, payload.user_id, &overview).await?;
+        }
+        kind => warn!(kind, job.id, "Skipping job of unknown kind."),
+    }
+
+    dal::complete_job(pool, job.id).await
+}
+
+/// Background worker that periodically purges exercises, workouts, and sets
+/// that have been sitting in the trash for longer than [`TRASH_RETENTION_DAYS`].
+async fn run_trash_purge_worker(pool: Pool<Sqlite>) {
+    loop {
+        let older_than = chrono::Utc::now() - chrono::Duration::days(TRASH_RETENTION_DAYS);
+        if let Err(err) = dal::purge_deleted(&pool, older_than).await {
+            error!(%err, "Failed to purge deleted rows.");
+        }
+
+        tokio::time::sleep(PURGE_INTERVAL).await;
+    }
+}
+
 async fn get_static_file(uri: Uri) -> Response {
     let path = match uri.path().trim_start_matches('/') {
         "" => "index.html",
@@ -135,10 +318,11 @@ async fn get_static_file(uri: Uri) -> Response {
 async fn check_workout_exists<T>(
     State(state): State<AppState>,
     Path(id): Path<i64>,
+    Extension(AuthUser(user_id)): Extension<AuthUser>,
     request: Request<T>,
     next: Next<T>,
 ) -> Response {
-    match dal::get_workout(&state.pool, id).await {
+    match dal::get_workout(&state.pool, user_id, id).await {
         Err(err) => {
             error!(%err, "Failed to check if workout exists.");
             StatusCode::INTERNAL_SERVER_ERROR.into_response()
@@ -151,10 +335,11 @@ async fn check_workout_exists<T>(
 async fn check_exercise_exists<T>(
     State(state): State<AppState>,
     Path(id): Path<i64>,
+    Extension(AuthUser(user_id)): Extension<AuthUser>,
     request: Request<T>,
     next: Next<T>,
 ) -> Response {
-    match dal::get_exercise(&state.pool, id).await {
+    match dal::get_exercise(&state.pool, user_id, id).await {
         Err(err) => {
             error!(%err, "Failed to check if exercise exists.");
             StatusCode::INTERNAL_SERVER_ERROR.into_response()
@@ -167,10 +352,11 @@ async fn check_exercise_exists<T>(
 async fn check_exercise_set_exists<T>(
     State(state): State<AppState>,
     Path(id): Path<i64>,
+    Extension(AuthUser(user_id)): Extension<AuthUser>,
     request: Request<T>,
     next: Next<T>,
 ) -> Response {
-    match dal::get_exercise_set(&state.pool, id).await {
+    match dal::get_exercise_set(&state.pool, user_id, id).await {
         Err(err) => {
             error!(%err, "Failed to check if exercise set exists.");
             StatusCode::INTERNAL_SERVER_ERROR.into_response()
@@ -180,18 +366,42 @@ async fn check_exercise_set_exists<T>(
     }
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/exercises/{id}",
+    params(("id" = i64, Path, description = "Id of the exercise")),
+    responses(
+        (status = 200, description = "The exercise was found", body = Exercise),
+        (status = 401, description = "Not authenticated"),
+        (status = 404, description = "No exercise with this id exists"),
+        (status = 500, description = "An internal error occurred"),
+    ),
+)]
 async fn get_exercise(
     State(state): State<AppState>,
     Path(id): Path<i64>,
+    Extension(AuthUser(user_id)): Extension<AuthUser>,
 ) -> Result<Json<Exercise>, AppError> {
-    dal::get_exercise(&state.pool, id)
+    dal::get_exercise(&state.pool, user_id, id)
         .await?
         .map(|exercise| Json(Exercise::from(exercise)))
         .ok_or_else(|| AppError::StatusCode(StatusCode::NOT_FOUND))
 }
 
-async fn get_exercises(State(state): State<AppState>) -> Result<Json<Vec<Exercise>>, AppError> {
-    let exercises = dal::get_exercises(&state.pool)
+#[utoipa::path(
+    get,
+    path = "/api/exercises",
+    responses(
+        (status = 200, description = "All known exercises", body = [Exercise]),
+        (status = 401, description = "Not authenticated"),
+        (status = 500, description = "An internal error occurred"),
+    ),
+)]
+async fn get_exercises(
+    State(state): State<AppState>,
+    Extension(AuthUser(user_id)): Extension<AuthUser>,
+) -> Result<Json<Vec<Exercise>>, AppError> {
+    let exercises = dal::get_exercises(&state.pool, user_id)
         .await?
         .into_iter()
         .map(Exercise::from)
@@ -199,53 +409,268 @@ async fn get_exercises(State(state): State<AppState>) -> Result<Json<Vec<Exercis
     Ok(Json(exercises))
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/exercises",
+    request_body = CreateUpdateExercise,
+    responses(
+        (status = 200, description = "The exercise was created", body = Exercise),
+        (status = 401, description = "Not authenticated"),
+        (status = 500, description = "An internal error occurred"),
+    ),
+)]
 async fn create_exercise(
     State(state): State<AppState>,
+    Extension(AuthUser(user_id)): Extension<AuthUser>,
     Json(exercise): Json<CreateUpdateExercise>,
 ) -> Result<Json<Exercise>, AppError> {
-    let exercise = dal::create_exercise(&state.pool, &exercise.name).await?;
+    let exercise = dal::create_exercise(&state.pool, user_id, &exercise.name).await?;
     Ok(Json(Exercise::from(exercise)))
 }
 
+#[utoipa::path(
+    put,
+    path = "/api/exercises/{id}",
+    params(("id" = i64, Path, description = "Id of the exercise")),
+    request_body = CreateUpdateExercise,
+    responses(
+        (status = 200, description = "The exercise was updated", body = Exercise),
+        (status = 401, description = "Not authenticated"),
+        (status = 404, description = "No exercise with this id exists"),
+        (status = 500, description = "An internal error occurred"),
+    ),
+)]
 async fn update_exercise(
     State(state): State<AppState>,
     Path(id): Path<i64>,
+    Extension(AuthUser(user_id)): Extension<AuthUser>,
     Json(exercise): Json<CreateUpdateExercise>,
 ) -> Result<Json<Exercise>, AppError> {
-    let exercise = dal::update_exercise(&state.pool, id, &exercise.name).await?;
+    let exercise = dal::update_exercise(&state.pool, user_id, id, &exercise.name).await?;
     Ok(Json(Exercise::from(exercise)))
 }
 
+#[utoipa::path(
+    delete,
+    path = "/api/exercises/{id}",
+    params(("id" = i64, Path, description = "Id of the exercise")),
+    responses(
+        (status = 204, description = "The exercise was deleted"),
+        (status = 401, description = "Not authenticated"),
+        (status = 404, description = "No exercise with this id exists"),
+        (status = 500, description = "An internal error occurred"),
+    ),
+)]
 async fn delete_exercise(
     State(state): State<AppState>,
     Path(id): Path<i64>,
+    Extension(AuthUser(user_id)): Extension<AuthUser>,
 ) -> Result<StatusCode, AppError> {
-    dal::delete_exercise(&state.pool, id)
+    let status = dal::delete_exercise(&state.pool, user_id, id)
         .await?
         .map(|_| StatusCode::NO_CONTENT)
-        .ok_or_else(|| AppError::StatusCode(StatusCode::NOT_FOUND))
+        .ok_or_else(|| AppError::StatusCode(StatusCode::NOT_FOUND))?;
+
+    dal::enqueue_recompute_statistics_job(&state.pool, user_id).await?;
+
+    Ok(status)
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/exercises/{id}/restore",
+    params(("id" = i64, Path, description = "Id of the exercise")),
+    responses(
+        (status = 200, description = "The exercise was restored from the trash", body = Exercise),
+        (status = 401, description = "Not authenticated"),
+        (status = 404, description = "No deleted exercise with this id exists"),
+        (status = 500, description = "An internal error occurred"),
+    ),
+)]
+async fn restore_exercise(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+    Extension(AuthUser(user_id)): Extension<AuthUser>,
+) -> Result<Json<Exercise>, AppError> {
+    let exercise = dal::restore_exercise(&state.pool, user_id, id)
+        .await?
+        .ok_or_else(|| AppError::StatusCode(StatusCode::NOT_FOUND))?;
+
+    dal::enqueue_recompute_statistics_job(&state.pool, user_id).await?;
+
+    Ok(Json(Exercise::from(exercise)))
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/exercises/{id}/count",
+    params(("id" = i64, Path, description = "Id of the exercise")),
+    responses(
+        (status = 200, description = "Number of sets logged for this exercise", body = ExerciseCount),
+        (status = 401, description = "Not authenticated"),
+        (status = 404, description = "No exercise with this id exists"),
+        (status = 500, description = "An internal error occurred"),
+    ),
+)]
 async fn get_exercise_count(
     State(state): State<AppState>,
     Path(id): Path<i64>,
+    Extension(AuthUser(user_id)): Extension<AuthUser>,
 ) -> Result<Json<responses::ExerciseCount>, AppError> {
-    let count = dal::get_exercise_count(&state.pool, id).await?;
+    let count = dal::get_exercise_count(&state.pool, user_id, id).await?;
     Ok(Json(ExerciseCount::from(count)))
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/exercises/{id}/records",
+    params(
+        ("id" = i64, Path, description = "Id of the exercise"),
+        ("preferredUnit" = Option<requests::WeightUnit>, Query, description = "Unit to express weights in; defaults to kg when omitted"),
+    ),
+    responses(
+        (status = 200, description = "Personal records and 1RM progression for this exercise", body = ExerciseRecords),
+        (status = 401, description = "Not authenticated"),
+        (status = 404, description = "No exercise with this id exists"),
+        (status = 500, description = "An internal error occurred"),
+    ),
+)]
+async fn get_exercise_records(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+    Extension(AuthUser(user_id)): Extension<AuthUser>,
+    Query(query): Query<requests::GetExerciseRecords>,
+) -> Result<Json<responses::ExerciseRecords>, AppError> {
+    let records =
+        dal::get_exercise_records(&state.pool, user_id, id, query.preferred_unit.into()).await?;
+    Ok(Json(responses::ExerciseRecords::from(records)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/exercises/{id}/image",
+    params(("id" = i64, Path, description = "Id of the exercise")),
+    responses(
+        (status = 204, description = "The image was stored"),
+        (status = 400, description = "The request did not contain an image part"),
+        (status = 401, description = "Not authenticated"),
+        (status = 404, description = "No exercise with this id exists"),
+        (status = 413, description = "The uploaded image exceeds the size limit"),
+        (status = 415, description = "The uploaded file is not a supported image format"),
+        (status = 500, description = "An internal error occurred"),
+    ),
+)]
+async fn upload_exercise_image(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+    Extension(AuthUser(user_id)): Extension<AuthUser>,
+    mut multipart: Multipart,
+) -> Result<StatusCode, AppError> {
+    // The route's body limit rejects oversize uploads while the multipart
+    // stream is read, so that error is surfaced as 413 rather than a 500.
+    let Some(field) = multipart.next_field().await.map_err(|err| match err.status() {
+        StatusCode::PAYLOAD_TOO_LARGE => AppError::StatusCode(StatusCode::PAYLOAD_TOO_LARGE),
+        _ => anyhow::anyhow!("Failed to read multipart request: {err}").into(),
+    })?
+    else {
+        return Err(AppError::StatusCode(StatusCode::BAD_REQUEST));
+    };
+
+    let data = field.bytes().await.map_err(|err| match err.status() {
+        StatusCode::PAYLOAD_TOO_LARGE => AppError::StatusCode(StatusCode::PAYLOAD_TOO_LARGE),
+        _ => anyhow::anyhow!("Failed to read uploaded image: {err}").into(),
+    })?;
+
+    if data.len() > MAX_IMAGE_BYTES {
+        return Err(AppError::StatusCode(StatusCode::PAYLOAD_TOO_LARGE));
+    }
+
+    let format = image::guess_format(&data)
+        .map_err(|_| AppError::StatusCode(StatusCode::UNSUPPORTED_MEDIA_TYPE))?;
+    let image = image::load_from_memory_with_format(&data, format)
+        .map_err(|_| AppError::StatusCode(StatusCode::UNSUPPORTED_MEDIA_TYPE))?;
+
+    let image = if image.width() > MAX_IMAGE_DIMENSION || image.height() > MAX_IMAGE_DIMENSION {
+        image.resize(
+            MAX_IMAGE_DIMENSION,
+            MAX_IMAGE_DIMENSION,
+            image::imageops::FilterType::Lanczos3,
+        )
+    } else {
+        image
+    };
+
+    // Re-encoding as PNG drops any embedded metadata (EXIF, ICC profiles, ...)
+    // that came with the upload.
+    let mut encoded = Vec::new();
+    image
+        .write_to(&mut std::io::Cursor::new(&mut encoded), image::ImageFormat::Png)
+        .map_err(|err| anyhow::anyhow!("Failed to re-encode image: {err}"))?;
+
+    dal::upsert_exercise_image(&state.pool, user_id, id, EXERCISE_IMAGE_CONTENT_TYPE, encoded)
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/exercises/{id}/image",
+    params(("id" = i64, Path, description = "Id of the exercise")),
+    responses(
+        (status = 200, description = "The stored image"),
+        (status = 401, description = "Not authenticated"),
+        (status = 404, description = "No exercise with this id exists, or it has no image"),
+        (status = 500, description = "An internal error occurred"),
+    ),
+)]
+async fn get_exercise_image(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+    Extension(AuthUser(user_id)): Extension<AuthUser>,
+) -> Result<Response, AppError> {
+    dal::get_exercise_image(&state.pool, user_id, id)
+        .await?
+        .map(|image| ([(CONTENT_TYPE, image.content_type)], image.data).into_response())
+        .ok_or_else(|| AppError::StatusCode(StatusCode::NOT_FOUND))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/workouts/{id}",
+    params(("id" = i64, Path, description = "Id of the workout")),
+    responses(
+        (status = 200, description = "The workout was found", body = Workout),
+        (status = 401, description = "Not authenticated"),
+        (status = 404, description = "No workout with this id exists"),
+        (status = 500, description = "An internal error occurred"),
+    ),
+)]
 async fn get_workout(
     State(state): State<AppState>,
     Path(id): Path<i64>,
+    Extension(AuthUser(user_id)): Extension<AuthUser>,
 ) -> Result<Json<Workout>, AppError> {
-    dal::get_workout(&state.pool, id)
+    dal::get_workout(&state.pool, user_id, id)
         .await?
         .map(|workout| Json(Workout::from(workout)))
         .ok_or_else(|| AppError::StatusCode(StatusCode::NOT_FOUND))
 }
 
-async fn get_workouts(State(state): State<AppState>) -> Result<Json<Vec<Workout>>, AppError> {
-    let workouts = dal::get_workouts(&state.pool)
+#[utoipa::path(
+    get,
+    path = "/api/workouts",
+    responses(
+        (status = 200, description = "All known workouts", body = [Workout]),
+        (status = 401, description = "Not authenticated"),
+        (status = 500, description = "An internal error occurred"),
+    ),
+)]
+async fn get_workouts(
+    State(state): State<AppState>,
+    Extension(AuthUser(user_id)): Extension<AuthUser>,
+) -> Result<Json<Vec<Workout>>, AppError> {
+    let workouts = dal::get_workouts(&state.pool, user_id)
         .await?
         .into_iter()
         .map(Workout::from)
@@ -253,35 +678,110 @@ async fn get_workouts(State(state): State<AppState>) -> Result<Json<Vec<Workout>
     Ok(Json(workouts))
 }
 
-async fn create_workout(State(state): State<AppState>) -> Result<Json<Workout>, AppError> {
-    let workout = dal::create_workout(&state.pool).await?;
+#[utoipa::path(
+    post,
+    path = "/api/workouts",
+    responses(
+        (status = 200, description = "The workout was created", body = Workout),
+        (status = 401, description = "Not authenticated"),
+        (status = 500, description = "An internal error occurred"),
+    ),
+)]
+async fn create_workout(
+    State(state): State<AppState>,
+    Extension(AuthUser(user_id)): Extension<AuthUser>,
+) -> Result<Json<Workout>, AppError> {
+    let workout = dal::create_workout(&state.pool, user_id).await?;
     Ok(Json(Workout::from(workout)))
 }
 
+#[utoipa::path(
+    delete,
+    path = "/api/workouts/{id}",
+    params(("id" = i64, Path, description = "Id of the workout")),
+    responses(
+        (status = 204, description = "The workout was deleted"),
+        (status = 401, description = "Not authenticated"),
+        (status = 404, description = "No workout with this id exists"),
+        (status = 500, description = "An internal error occurred"),
+    ),
+)]
 async fn delete_workout(
     State(state): State<AppState>,
     Path(id): Path<i64>,
+    Extension(AuthUser(user_id)): Extension<AuthUser>,
 ) -> Result<StatusCode, AppError> {
-    dal::delete_workout(&state.pool, id)
+    let status = dal::delete_workout(&state.pool, user_id, id)
         .await?
         .map(|_| StatusCode::NO_CONTENT)
-        .ok_or_else(|| AppError::StatusCode(StatusCode::NOT_FOUND))
+        .ok_or_else(|| AppError::StatusCode(StatusCode::NOT_FOUND))?;
+
+    dal::enqueue_recompute_statistics_job(&state.pool, user_id).await?;
+
+    Ok(status)
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/workouts/{id}/restore",
+    params(("id" = i64, Path, description = "Id of the workout")),
+    responses(
+        (status = 200, description = "The workout was restored from the trash", body = Workout),
+        (status = 401, description = "Not authenticated"),
+        (status = 404, description = "No deleted workout with this id exists"),
+        (status = 500, description = "An internal error occurred"),
+    ),
+)]
+async fn restore_workout(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+    Extension(AuthUser(user_id)): Extension<AuthUser>,
+) -> Result<Json<Workout>, AppError> {
+    let workout = dal::restore_workout(&state.pool, user_id, id)
+        .await?
+        .ok_or_else(|| AppError::StatusCode(StatusCode::NOT_FOUND))?;
+
+    dal::enqueue_recompute_statistics_job(&state.pool, user_id).await?;
+
+    Ok(Json(Workout::from(workout)))
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/sets/{id}",
+    params(("id" = i64, Path, description = "Id of the exercise set")),
+    responses(
+        (status = 200, description = "The exercise set was found", body = ExerciseSet),
+        (status = 401, description = "Not authenticated"),
+        (status = 404, description = "No exercise set with this id exists"),
+        (status = 500, description = "An internal error occurred"),
+    ),
+)]
 async fn get_exercise_set(
     State(state): State<AppState>,
     Path(id): Path<i64>,
+    Extension(AuthUser(user_id)): Extension<AuthUser>,
 ) -> Result<Json<ExerciseSet>, AppError> {
-    dal::get_exercise_set(&state.pool, id)
+    dal::get_exercise_set(&state.pool, user_id, id)
         .await?
         .map(|exercise| Json(ExerciseSet::from(exercise)))
         .ok_or_else(|| AppError::StatusCode(StatusCode::NOT_FOUND))
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/sets",
+    responses(
+        (status = 200, description = "All known exercise sets", body = [ExerciseSet]),
+        (status = 401, description = "Not authenticated"),
+        (status = 500, description = "An internal error occurred"),
+    ),
+)]
 async fn get_exercise_sets(
     State(state): State<AppState>,
+    Extension(AuthUser(user_id)): Extension<AuthUser>,
 ) -> Result<Json<Vec<ExerciseSet>>, AppError> {
-    let exercise_sets = dal::get_exercise_sets(&state.pool)
+    let exercise_sets = dal::get_exercise_sets(&state.pool, user_id)
         .await?
         .into_iter()
         .map(ExerciseSet::from)
@@ -289,11 +789,23 @@ async fn get_exercise_sets(
     Ok(Json(exercise_sets))
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/workouts/{id}/sets",
+    params(("id" = i64, Path, description = "Id of the workout")),
+    responses(
+        (status = 200, description = "Exercise sets logged for this workout", body = [ExerciseSet]),
+        (status = 401, description = "Not authenticated"),
+        (status = 404, description = "No workout with this id exists"),
+        (status = 500, description = "An internal error occurred"),
+    ),
+)]
 async fn get_exercise_sets_by_workout_id(
     State(state): State<AppState>,
     Path(id): Path<i64>,
+    Extension(AuthUser(user_id)): Extension<AuthUser>,
 ) -> Result<Json<Vec<ExerciseSet>>, AppError> {
-    let exercise_sets = dal::get_exercise_sets_by_workout_id(&state.pool, id)
+    let exercise_sets = dal::get_exercise_sets_by_workout_id(&state.pool, user_id, id)
         .await?
         .into_iter()
         .map(ExerciseSet::from)
@@ -301,78 +813,488 @@ async fn get_exercise_sets_by_workout_id(
     Ok(Json(exercise_sets))
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/exercises/{id}/sets",
+    params(("id" = i64, Path, description = "Id of the exercise")),
+    responses(
+        (status = 200, description = "Exercise sets logged for this exercise", body = [ExerciseSet]),
+        (status = 401, description = "Not authenticated"),
+        (status = 404, description = "No exercise with this id exists"),
+        (status = 500, description = "An internal error occurred"),
+    ),
+)]
 async fn get_exercise_sets_by_exercise_id(
     State(state): State<AppState>,
     Path(id): Path<i64>,
+    Extension(AuthUser(user_id)): Extension<AuthUser>,
+) -> Result<Json<Vec<ExerciseSet>>, AppError> {
+    let exercise_sets = dal::get_exercise_sets_by_exercise_id(&state.pool, user_id, id)
+        .await?
+        .into_iter()
+        .map(ExerciseSet::from)
+        .collect();
+    Ok(Json(exercise_sets))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/sets/search",
+    params(
+        ("exerciseId" = Option<i64>, Query, description = "Only include sets of this exercise"),
+        ("workoutId" = Option<i64>, Query, description = "Only include sets from this workout"),
+        ("after" = Option<i64>, Query, description = "Only include sets logged at or after this Unix timestamp"),
+        ("before" = Option<i64>, Query, description = "Only include sets logged at or before this Unix timestamp"),
+        ("minWeight" = Option<i64>, Query, description = "Only include sets with at least this weight"),
+        ("maxWeight" = Option<i64>, Query, description = "Only include sets with at most this weight"),
+        ("minReps" = Option<i64>, Query, description = "Only include sets with at least this many repetitions"),
+        ("maxReps" = Option<i64>, Query, description = "Only include sets with at most this many repetitions"),
+        ("noteContains" = Option<String>, Query, description = "Only include sets whose note contains this substring"),
+        ("limit" = Option<i64>, Query, description = "Maximum number of sets to return"),
+        ("offset" = Option<i64>, Query, description = "Number of matching sets to skip"),
+        ("reverse" = Option<bool>, Query, description = "Order newest first instead of oldest first"),
+    ),
+    responses(
+        (status = 200, description = "Exercise sets matching the given filters", body = [ExerciseSet]),
+        (status = 401, description = "Not authenticated"),
+        (status = 500, description = "An internal error occurred"),
+    ),
+)]
+async fn search_exercise_sets(
+    State(state): State<AppState>,
+    Extension(AuthUser(user_id)): Extension<AuthUser>,
+    Query(query): Query<requests::SearchExerciseSets>,
 ) -> Result<Json<Vec<ExerciseSet>>, AppError> {
-    let exercise_sets = dal::get_exercise_sets_by_exercise_id(&state.pool, id)
+    let filters = dal::ExerciseSetFilters {
+        exercise_id: query.exercise_id,
+        workout_id: query.workout_id,
+        after: query.after.and_then(|secs| chrono::DateTime::from_timestamp(secs, 0)),
+        before: query.before.and_then(|secs| chrono::DateTime::from_timestamp(secs, 0)),
+        min_weight: query.min_weight,
+        max_weight: query.max_weight,
+        min_repetitions: query.min_repetitions,
+        max_repetitions: query.max_repetitions,
+        note_contains: query.note_contains,
+        limit: query.limit,
+        offset: query.offset,
+        reverse: query.reverse,
+    };
+
+    let exercise_sets = dal::get_exercise_sets_filtered(&state.pool, user_id, &filters)
         .await?
         .into_iter()
         .map(ExerciseSet::from)
         .collect();
+
     Ok(Json(exercise_sets))
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/sets",
+    request_body = CreateUpdateExerciseSet,
+    responses(
+        (status = 200, description = "The exercise set was created", body = ExerciseSet),
+        (status = 401, description = "Not authenticated"),
+        (status = 404, description = "No exercise or workout with this id exists"),
+        (status = 500, description = "An internal error occurred"),
+    ),
+)]
 async fn create_exercise_set(
     State(state): State<AppState>,
+    Extension(AuthUser(user_id)): Extension<AuthUser>,
     Json(exercise_set): Json<CreateUpdateExerciseSet>,
 ) -> Result<Json<ExerciseSet>, AppError> {
+    if dal::get_exercise(&state.pool, user_id, exercise_set.exercise_id)
+        .await?
+        .is_none()
+    {
+        return Err(AppError::StatusCode(StatusCode::NOT_FOUND));
+    }
+    if dal::get_workout(&state.pool, user_id, exercise_set.workout_id)
+        .await?
+        .is_none()
+    {
+        return Err(AppError::StatusCode(StatusCode::NOT_FOUND));
+    }
+
     let exercise_set = dal::create_or_update_exercise_set(
         &state.pool,
+        user_id,
         None,
         exercise_set.workout_id,
         exercise_set.exercise_id,
         exercise_set.repetitions,
         exercise_set.weight,
+        exercise_set.weight_unit.into(),
         exercise_set.note,
     )
     .await?;
-    Ok(Json(ExerciseSet::from(exercise_set)))
+    let exercise_set = ExerciseSet::from(exercise_set);
+
+    state
+        .set_events
+        .send(SetEvent::created(user_id, exercise_set.clone()))
+        .ok();
+
+    dal::enqueue_recompute_statistics_job(&state.pool, user_id).await?;
+
+    Ok(Json(exercise_set))
 }
 
+#[utoipa::path(
+    put,
+    path = "/api/sets/{id}",
+    params(("id" = i64, Path, description = "Id of the exercise set")),
+    request_body = CreateUpdateExerciseSet,
+    responses(
+        (status = 200, description = "The exercise set was updated", body = ExerciseSet),
+        (status = 401, description = "Not authenticated"),
+        (status = 404, description = "No exercise set, exercise, or workout with this id exists"),
+        (status = 500, description = "An internal error occurred"),
+    ),
+)]
 async fn update_exercise_set(
     State(state): State<AppState>,
     Path(id): Path<i64>,
+    Extension(AuthUser(user_id)): Extension<AuthUser>,
     Json(exercise_set): Json<CreateUpdateExerciseSet>,
 ) -> Result<Json<ExerciseSet>, AppError> {
+    if dal::get_exercise(&state.pool, user_id, exercise_set.exercise_id)
+        .await?
+        .is_none()
+    {
+        return Err(AppError::StatusCode(StatusCode::NOT_FOUND));
+    }
+    if dal::get_workout(&state.pool, user_id, exercise_set.workout_id)
+        .await?
+        .is_none()
+    {
+        return Err(AppError::StatusCode(StatusCode::NOT_FOUND));
+    }
+
     let exercise_set = dal::create_or_update_exercise_set(
         &state.pool,
+        user_id,
         Some(id),
         exercise_set.workout_id,
         exercise_set.exercise_id,
         exercise_set.repetitions,
         exercise_set.weight,
+        exercise_set.weight_unit.into(),
         exercise_set.note,
     )
     .await?;
-    Ok(Json(ExerciseSet::from(exercise_set)))
+    let exercise_set = ExerciseSet::from(exercise_set);
+
+    state
+        .set_events
+        .send(SetEvent::updated(user_id, exercise_set.clone()))
+        .ok();
+
+    dal::enqueue_recompute_statistics_job(&state.pool, user_id).await?;
+
+    Ok(Json(exercise_set))
 }
 
+#[utoipa::path(
+    delete,
+    path = "/api/sets/{id}",
+    params(("id" = i64, Path, description = "Id of the exercise set")),
+    responses(
+        (status = 204, description = "The exercise set was deleted"),
+        (status = 401, description = "Not authenticated"),
+        (status = 404, description = "No exercise set with this id exists"),
+        (status = 500, description = "An internal error occurred"),
+    ),
+)]
 async fn delete_exercise_set(
     State(state): State<AppState>,
     Path(id): Path<i64>,
+    Extension(AuthUser(user_id)): Extension<AuthUser>,
 ) -> Result<StatusCode, AppError> {
-    dal::delete_exercise_set(&state.pool, id)
+    let existing = dal::get_exercise_set(&state.pool, user_id, id).await?;
+
+    let status = dal::delete_exercise_set(&state.pool, user_id, id)
         .await?
         .map(|_| StatusCode::NO_CONTENT)
-        .ok_or_else(|| AppError::StatusCode(StatusCode::NOT_FOUND))
+        .ok_or_else(|| AppError::StatusCode(StatusCode::NOT_FOUND))?;
+
+    if let Some(existing) = existing {
+        state
+            .set_events
+            .send(SetEvent::deleted(user_id, existing.workout_id, id))
+            .ok();
+
+        dal::enqueue_recompute_statistics_job(&state.pool, user_id).await?;
+    }
+
+    Ok(status)
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/sets/{id}/restore",
+    params(("id" = i64, Path, description = "Id of the exercise set")),
+    responses(
+        (status = 200, description = "The exercise set was restored from the trash", body = ExerciseSet),
+        (status = 401, description = "Not authenticated"),
+        (status = 404, description = "No deleted exercise set with this id exists"),
+        (status = 500, description = "An internal error occurred"),
+    ),
+)]
+async fn restore_exercise_set(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+    Extension(AuthUser(user_id)): Extension<AuthUser>,
+) -> Result<Json<ExerciseSet>, AppError> {
+    let Some(exercise_set) = dal::restore_exercise_set(&state.pool, user_id, id).await? else {
+        return Err(AppError::StatusCode(StatusCode::NOT_FOUND));
+    };
+    let exercise_set = ExerciseSet::from(exercise_set);
+
+    state
+        .set_events
+        .send(SetEvent::created(user_id, exercise_set.clone()))
+        .ok();
+
+    dal::enqueue_recompute_statistics_job(&state.pool, user_id).await?;
+
+    Ok(Json(exercise_set))
+}
+
+async fn get_exercise_set_stream(
+    State(state): State<AppState>,
+    Path(workout_id): Path<i64>,
+    Extension(AuthUser(user_id)): Extension<AuthUser>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let stream = BroadcastStream::new(state.set_events.subscribe()).filter_map(move |event| {
+        std::future::ready(match event {
+            Ok(event) if event.user_id == user_id && event.workout_id == workout_id => {
+                Some(Ok(event.into_sse_event()))
+            }
+            _ => None,
+        })
+    });
+
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/workouts/{id}/sets/suggest",
+    params(("id" = i64, Path, description = "Id of the workout")),
+    request_body = GetSetSuggestion,
+    responses(
+        (status = 200, description = "Suggested repetitions and weight for the next set", body = SetSuggestion),
+        (status = 401, description = "Not authenticated"),
+        (status = 500, description = "An internal error occurred"),
+    ),
+)]
 async fn get_set_suggestion(
     State(state): State<AppState>,
     Path(id): Path<i64>,
+    Extension(AuthUser(user_id)): Extension<AuthUser>,
     Json(request): Json<GetSetSuggestion>,
 ) -> Result<Json<SetSuggestion>, AppError> {
-    let suggestion =
-        dal::get_set_suggestion_for_workout(&state.pool, id, request.exercise_id).await?;
+    let suggestion = dal::get_set_suggestion_for_workout(
+        &state.pool,
+        user_id,
+        id,
+        request.exercise_id,
+        request.preferred_unit.into(),
+    )
+    .await?;
     Ok(Json(SetSuggestion::from(suggestion)))
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/statistics",
+    responses(
+        (status = 200, description = "Aggregate statistics over all workouts", body = StatisticsOverview),
+        (status = 401, description = "Not authenticated"),
+        (status = 500, description = "An internal error occurred"),
+    ),
+)]
 async fn get_statistics_overview(
     State(state): State<AppState>,
+    Extension(AuthUser(user_id)): Extension<AuthUser>,
 ) -> Result<Json<StatisticsOverview>, AppError> {
-    let overview = dal::get_statistics_overview(&state.pool).await?;
-    Ok(Json(StatisticsOverview::from(overview)))
+    let overview = match dal::get_cached_statistics_overview(&state.pool, user_id).await? {
+        Some(cached) => StatisticsOverview::from(cached),
+        None => {
+            let overview = dal::get_statistics_overview(&state.pool, user_id).await?;
+            StatisticsOverview::from(overview)
+        }
+    };
+
+    Ok(Json(overview))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/statistics/bodyweight-vs-volume",
+    responses(
+        (status = 200, description = "Bodyweight trend alongside training volume per workout", body = [responses::BodyweightVolumePoint]),
+        (status = 401, description = "Not authenticated"),
+        (status = 500, description = "An internal error occurred"),
+    ),
+)]
+async fn get_bodyweight_vs_training_volume(
+    State(state): State<AppState>,
+    Extension(AuthUser(user_id)): Extension<AuthUser>,
+) -> Result<Json<Vec<responses::BodyweightVolumePoint>>, AppError> {
+    let points = dal::get_bodyweight_vs_training_volume(&state.pool, user_id)
+        .await?
+        .into_iter()
+        .map(responses::BodyweightVolumePoint::from)
+        .collect();
+
+    Ok(Json(points))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/measurements",
+    request_body = requests::CreateMeasurement,
+    responses(
+        (status = 200, description = "The measurement was recorded", body = responses::Measurement),
+        (status = 401, description = "Not authenticated"),
+        (status = 500, description = "An internal error occurred"),
+    ),
+)]
+async fn create_measurement(
+    State(state): State<AppState>,
+    Extension(AuthUser(user_id)): Extension<AuthUser>,
+    Json(measurement): Json<requests::CreateMeasurement>,
+) -> Result<Json<responses::Measurement>, AppError> {
+    let measurement =
+        dal::create_measurement(&state.pool, user_id, &measurement.kind, measurement.value).await?;
+    Ok(Json(responses::Measurement::from(measurement)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/measurements",
+    params(
+        ("kind" = Option<String>, Query, description = "Only include measurements of this kind"),
+        ("after" = Option<i64>, Query, description = "Only include measurements recorded at or after this Unix timestamp"),
+        ("before" = Option<i64>, Query, description = "Only include measurements recorded at or before this Unix timestamp"),
+    ),
+    responses(
+        (status = 200, description = "Measurements matching the given filters, newest first", body = [responses::Measurement]),
+        (status = 401, description = "Not authenticated"),
+        (status = 500, description = "An internal error occurred"),
+    ),
+)]
+async fn get_measurements(
+    State(state): State<AppState>,
+    Extension(AuthUser(user_id)): Extension<AuthUser>,
+    Query(query): Query<requests::GetMeasurements>,
+) -> Result<Json<Vec<responses::Measurement>>, AppError> {
+    let measurements = dal::get_measurements(
+        &state.pool,
+        user_id,
+        query.kind.as_deref(),
+        query.after.and_then(|secs| chrono::DateTime::from_timestamp(secs, 0)),
+        query.before.and_then(|secs| chrono::DateTime::from_timestamp(secs, 0)),
+    )
+    .await?
+    .into_iter()
+    .map(responses::Measurement::from)
+    .collect();
+
+    Ok(Json(measurements))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/measurements/{id}",
+    params(("id" = i64, Path, description = "Id of the measurement")),
+    request_body = requests::UpdateMeasurement,
+    responses(
+        (status = 200, description = "The measurement was updated", body = responses::Measurement),
+        (status = 401, description = "Not authenticated"),
+        (status = 404, description = "No measurement with this id exists"),
+        (status = 500, description = "An internal error occurred"),
+    ),
+)]
+async fn update_measurement(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+    Extension(AuthUser(user_id)): Extension<AuthUser>,
+    Json(measurement): Json<requests::UpdateMeasurement>,
+) -> Result<Json<responses::Measurement>, AppError> {
+    dal::update_measurement(&state.pool, user_id, id, measurement.value)
+        .await?
+        .map(|measurement| Json(responses::Measurement::from(measurement)))
+        .ok_or_else(|| AppError::StatusCode(StatusCode::NOT_FOUND))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/measurements/{id}",
+    params(("id" = i64, Path, description = "Id of the measurement")),
+    responses(
+        (status = 204, description = "The measurement was deleted"),
+        (status = 401, description = "Not authenticated"),
+        (status = 404, description = "No measurement with this id exists"),
+        (status = 500, description = "An internal error occurred"),
+    ),
+)]
+async fn delete_measurement(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+    Extension(AuthUser(user_id)): Extension<AuthUser>,
+) -> Result<StatusCode, AppError> {
+    dal::delete_measurement(&state.pool, user_id, id)
+        .await?
+        .map(|_| StatusCode::NO_CONTENT)
+        .ok_or_else(|| AppError::StatusCode(StatusCode::NOT_FOUND))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/backup",
+    responses(
+        (status = 200, description = "A portable snapshot of all exercises, workouts, and exercise sets", body = Backup),
+        (status = 401, description = "Not authenticated"),
+        (status = 500, description = "An internal error occurred"),
+    ),
+)]
+async fn export_backup(
+    State(state): State<AppState>,
+    Extension(AuthUser(user_id)): Extension<AuthUser>,
+) -> Result<Json<responses::Backup>, AppError> {
+    let backup = dal::export_backup(&state.pool, user_id).await?;
+    Ok(Json(responses::Backup::from(backup)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/backup",
+    request_body = responses::Backup,
+    responses(
+        (status = 200, description = "The backup was imported", body = ImportSummary),
+        (status = 400, description = "The backup document is malformed or its schema version is unsupported"),
+        (status = 401, description = "Not authenticated"),
+        (status = 500, description = "An internal error occurred"),
+    ),
+)]
+async fn import_backup(
+    State(state): State<AppState>,
+    Extension(AuthUser(user_id)): Extension<AuthUser>,
+    Json(backup): Json<responses::Backup>,
+) -> Result<Json<responses::ImportSummary>, AppError> {
+    if backup.schema_version != dal::BACKUP_SCHEMA_VERSION {
+        return Err(AppError::StatusCode(StatusCode::BAD_REQUEST));
+    }
+
+    let backup = dal::BackupEntity::try_from(backup)
+        .map_err(|_| AppError::StatusCode(StatusCode::BAD_REQUEST))?;
+    let summary = dal::import_backup(&state.pool, user_id, &backup).await?;
+    Ok(Json(responses::ImportSummary::from(summary)))
 }
 
 #[derive(Debug)]
@@ -404,15 +1326,201 @@ impl IntoResponse for AppError {
     }
 }
 
-mod requests {
+mod auth {
+    use argon2::{
+        password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
+        Argon2,
+    };
+    use axum::{
+        extract::State,
+        http::{Request, StatusCode},
+        middleware::Next,
+        response::{IntoResponse, Response},
+        Json,
+    };
+    use axum_extra::extract::{
+        cookie::{Cookie, SameSite},
+        CookieJar,
+    };
+    use chrono::{Duration, Utc};
+    use jsonwebtoken::{DecodingKey, EncodingKey, Header, Validation};
+    use rand::rngs::OsRng;
     use serde::{Deserialize, Serialize};
+    use tracing::error;
+
+    use crate::dal;
+
+    use super::{AppError, AppState};
+
+    const COOKIE_NAME: &str = "auth_token";
+    const TOKEN_LIFETIME_DAYS: i64 = 7;
+
+    /// Id of the user that a valid auth cookie was issued to, injected into
+    /// request extensions by [`require_auth`].
+    #[derive(Debug, Clone, Copy)]
+    pub struct AuthUser(pub i64);
 
     #[derive(Debug, Serialize, Deserialize)]
+    struct Claims {
+        sub: i64,
+        exp: i64,
+    }
+
+    #[derive(Debug, Deserialize)]
+    pub struct Credentials {
+        pub username: String,
+        pub password: String,
+    }
+
+    pub async fn register(
+        State(state): State<AppState>,
+        Json(credentials): Json<Credentials>,
+    ) -> Result<StatusCode, AppError> {
+        if dal::get_user_by_username(&state.pool, &credentials.username)
+            .await?
+            .is_some()
+        {
+            return Err(AppError::StatusCode(StatusCode::CONFLICT));
+        }
+
+        let salt = SaltString::generate(&mut OsRng);
+        let password_hash = Argon2::default()
+            .hash_password(credentials.password.as_bytes(), &salt)
+            .map_err(|err| anyhow::anyhow!("Failed to hash password: {err}"))?
+            .to_string();
+
+        match dal::create_user(&state.pool, &credentials.username, &password_hash).await {
+            Ok(_) => Ok(StatusCode::CREATED),
+            Err(err) if is_unique_violation(&err) => Err(AppError::StatusCode(StatusCode::CONFLICT)),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// SQLite's extended result code for a UNIQUE constraint violation.
+    const SQLITE_CONSTRAINT_UNIQUE: &str = "2067";
+
+    /// Whether `err` was ultimately caused by a SQLite UNIQUE constraint
+    /// violation, e.g. a race between two concurrent inserts that both passed
+    /// an application-level uniqueness check.
+    fn is_unique_violation(err: &anyhow::Error) -> bool {
+        err.downcast_ref::<sqlx::Error>()
+            .and_then(|err| err.as_database_error())
+            .is_some_and(|err| err.code().as_deref() == Some(SQLITE_CONSTRAINT_UNIQUE))
+    }
+
+    pub async fn login(
+        State(state): State<AppState>,
+        jar: CookieJar,
+        Json(credentials): Json<Credentials>,
+    ) -> Result<(CookieJar, StatusCode), AppError> {
+        let Some(user) = dal::get_user_by_username(&state.pool, &credentials.username).await?
+        else {
+            return Err(AppError::StatusCode(StatusCode::UNAUTHORIZED));
+        };
+
+        let hash = PasswordHash::new(&user.password_hash)
+            .map_err(|err| anyhow::anyhow!("Failed to parse stored password hash: {err}"))?;
+
+        if Argon2::default()
+            .verify_password(credentials.password.as_bytes(), &hash)
+            .is_err()
+        {
+            return Err(AppError::StatusCode(StatusCode::UNAUTHORIZED));
+        }
+
+        let token = encode_token(user.id, &state.jwt_secret)?;
+
+        // A cross-origin frontend needs `SameSite=None` for the browser to
+        // send the cookie at all, which in turn requires `Secure`. Same-site
+        // deployments keep the stricter `Lax` default.
+        let mut cookie = Cookie::build(COOKIE_NAME, token).http_only(true).path("/");
+        cookie = if state.cross_origin_cookies {
+            cookie.same_site(SameSite::None).secure(true)
+        } else {
+            cookie.same_site(SameSite::Lax)
+        };
+
+        Ok((jar.add(cookie.finish()), StatusCode::NO_CONTENT))
+    }
+
+    fn encode_token(user_id: i64, secret: &str) -> Result<String, AppError> {
+        let claims = Claims {
+            sub: user_id,
+            exp: (Utc::now() + Duration::days(TOKEN_LIFETIME_DAYS)).timestamp(),
+        };
+
+        jsonwebtoken::encode(
+            &Header::default(),
+            &claims,
+            &EncodingKey::from_secret(secret.as_bytes()),
+        )
+        .map_err(|err| anyhow::anyhow!("Failed to encode auth token: {err}").into())
+    }
+
+    pub async fn require_auth<T>(
+        State(state): State<AppState>,
+        jar: CookieJar,
+        mut request: Request<T>,
+        next: Next<T>,
+    ) -> Response {
+        let Some(cookie) = jar.get(COOKIE_NAME) else {
+            return StatusCode::UNAUTHORIZED.into_response();
+        };
+
+        let claims = match jsonwebtoken::decode::<Claims>(
+            cookie.value(),
+            &DecodingKey::from_secret(state.jwt_secret.as_bytes()),
+            &Validation::default(),
+        ) {
+            Ok(data) => data.claims,
+            Err(err) => {
+                error!(%err, "Failed to decode auth token.");
+                return StatusCode::UNAUTHORIZED.into_response();
+            }
+        };
+
+        request.extensions_mut().insert(AuthUser(claims.sub));
+
+        next.run(request).await
+    }
+}
+
+mod requests {
+    use serde::{Deserialize, Serialize};
+    use utoipa::ToSchema;
+
+    /// Unit a set's weight is expressed in, over the wire.
+    #[derive(Debug, Clone, Copy, Serialize, Deserialize, ToSchema)]
+    #[serde(rename_all = "lowercase")]
+    pub enum WeightUnit {
+        Kg,
+        Lb,
+    }
+
+    impl From<WeightUnit> for crate::dal::WeightUnit {
+        fn from(value: WeightUnit) -> Self {
+            match value {
+                WeightUnit::Kg => crate::dal::WeightUnit::Kg,
+                WeightUnit::Lb => crate::dal::WeightUnit::Lb,
+            }
+        }
+    }
+
+    impl From<crate::dal::WeightUnit> for WeightUnit {
+        fn from(value: crate::dal::WeightUnit) -> Self {
+            match value {
+                crate::dal::WeightUnit::Kg => WeightUnit::Kg,
+                crate::dal::WeightUnit::Lb => WeightUnit::Lb,
+            }
+        }
+    }
+
+    #[derive(Debug, Serialize, Deserialize, ToSchema)]
     pub struct CreateUpdateExercise {
         pub name: String,
     }
 
-    #[derive(Debug, Serialize, Deserialize)]
+    #[derive(Debug, Serialize, Deserialize, ToSchema)]
     pub struct CreateUpdateExerciseSet {
         #[serde(rename = "workoutId")]
         pub workout_id: i64,
@@ -420,40 +1528,112 @@ mod requests {
         pub exercise_id: i64,
         pub repetitions: i64,
         pub weight: i64,
+        #[serde(rename = "weightUnit")]
+        pub weight_unit: WeightUnit,
         pub note: String,
     }
 
-    #[derive(Debug, Serialize, Deserialize)]
+    #[derive(Debug, Serialize, Deserialize, ToSchema)]
     pub struct GetSetSuggestion {
         #[serde(rename = "exerciseId")]
         pub exercise_id: Option<i64>,
+        /// Unit the suggested weight should be expressed in. Defaults to
+        /// kilograms when omitted.
+        #[serde(rename = "preferredUnit", default = "default_preferred_unit")]
+        pub preferred_unit: WeightUnit,
+    }
+
+    #[derive(Debug, Deserialize)]
+    pub struct GetExerciseRecords {
+        /// Unit weights should be expressed in. Defaults to kilograms when
+        /// omitted.
+        #[serde(rename = "preferredUnit", default = "default_preferred_unit")]
+        pub preferred_unit: WeightUnit,
+    }
+
+    fn default_preferred_unit() -> WeightUnit {
+        WeightUnit::Kg
+    }
+
+    #[derive(Debug, Deserialize)]
+    pub struct SearchExerciseSets {
+        #[serde(rename = "exerciseId")]
+        pub exercise_id: Option<i64>,
+        #[serde(rename = "workoutId")]
+        pub workout_id: Option<i64>,
+        /// Only include sets logged at or after this Unix timestamp.
+        pub after: Option<i64>,
+        /// Only include sets logged at or before this Unix timestamp.
+        pub before: Option<i64>,
+        #[serde(rename = "minWeight")]
+        pub min_weight: Option<i64>,
+        #[serde(rename = "maxWeight")]
+        pub max_weight: Option<i64>,
+        #[serde(rename = "minReps")]
+        pub min_repetitions: Option<i64>,
+        #[serde(rename = "maxReps")]
+        pub max_repetitions: Option<i64>,
+        #[serde(rename = "noteContains")]
+        pub note_contains: Option<String>,
+        pub limit: Option<i64>,
+        pub offset: Option<i64>,
+        #[serde(default)]
+        pub reverse: bool,
+    }
+
+    #[derive(Debug, Serialize, Deserialize, ToSchema)]
+    pub struct CreateMeasurement {
+        pub kind: String,
+        pub value: f64,
+    }
+
+    #[derive(Debug, Serialize, Deserialize, ToSchema)]
+    pub struct UpdateMeasurement {
+        pub value: f64,
+    }
+
+    #[derive(Debug, Deserialize)]
+    pub struct GetMeasurements {
+        pub kind: Option<String>,
+        /// Only include measurements recorded at or after this Unix timestamp.
+        pub after: Option<i64>,
+        /// Only include measurements recorded at or before this Unix timestamp.
+        pub before: Option<i64>,
     }
 }
 
 mod responses {
     use serde::{Deserialize, Serialize};
+    use utoipa::ToSchema;
 
     use crate::dal::{
-        ExerciseCountEntity, ExerciseEntity, ExerciseSetEntity, SetSuggestionEntity,
-        StatisticsOverviewEntity, WorkoutEntity,
+        BackupEntity, BodyweightVolumePointEntity, ExerciseCountEntity, ExerciseEntity,
+        ExerciseProgressionPointEntity, ExerciseRecordsEntity, ExerciseSetEntity, ImportSummaryEntity,
+        MeasurementEntity, SetSuggestionEntity, StatisticsCacheEntity, StatisticsOverviewEntity,
+        WorkoutEntity,
     };
 
-    #[derive(Debug, Deserialize, Serialize)]
+    #[derive(Debug, Deserialize, Serialize, ToSchema)]
     pub struct Exercise {
         pub id: i64,
         pub name: String,
+        #[serde(rename = "imageUrl")]
+        pub image_url: Option<String>,
     }
 
     impl From<ExerciseEntity> for Exercise {
         fn from(value: ExerciseEntity) -> Self {
             Self {
                 id: value.id,
+                image_url: value
+                    .has_image
+                    .then(|| format!("/api/exercises/{}/image", value.id)),
                 name: value.name,
             }
         }
     }
 
-    #[derive(Debug, Deserialize, Serialize)]
+    #[derive(Debug, Deserialize, Serialize, ToSchema)]
     pub struct Workout {
         pub id: i64,
         #[serde(rename = "createdUtcSeconds")]
@@ -469,7 +1649,7 @@ mod responses {
         }
     }
 
-    #[derive(Debug, Deserialize, Serialize)]
+    #[derive(Debug, Clone, Deserialize, Serialize, ToSchema)]
     pub struct ExerciseSet {
         pub id: i64,
         #[serde(rename = "exerciseId")]
@@ -482,6 +1662,8 @@ mod responses {
         pub created_utc_s: i64,
         pub repetitions: i64,
         pub weight: i64,
+        #[serde(rename = "weightUnit")]
+        pub weight_unit: super::requests::WeightUnit,
         pub note: Option<String>,
     }
 
@@ -495,17 +1677,22 @@ mod responses {
                 created_utc_s: value.created.timestamp(),
                 repetitions: value.repetitions,
                 weight: value.weight,
+                weight_unit: value.weight_unit.into(),
                 note: value.note,
             }
         }
     }
 
-    #[derive(Debug, Serialize)]
+    #[derive(Debug, Serialize, ToSchema)]
     pub struct SetSuggestion {
         #[serde(rename = "exerciseId")]
         pub exercise_id: i64,
         pub repetitions: i64,
         pub weight: i64,
+        #[serde(rename = "weightUnit")]
+        pub weight_unit: super::requests::WeightUnit,
+        #[serde(rename = "e1RM")]
+        pub e1rm: f64,
     }
 
     impl From<SetSuggestionEntity> for SetSuggestion {
@@ -514,11 +1701,13 @@ mod responses {
                 exercise_id: value.exercise_id,
                 repetitions: value.repetitions,
                 weight: value.weight,
+                weight_unit: value.weight_unit.into(),
+                e1rm: value.e1rm,
             }
         }
     }
 
-    #[derive(Debug, Serialize)]
+    #[derive(Debug, Serialize, ToSchema)]
     pub struct ExerciseCount {
         pub count: i64,
     }
@@ -529,7 +1718,55 @@ mod responses {
         }
     }
 
-    #[derive(Debug, Serialize)]
+    #[derive(Debug, Serialize, ToSchema)]
+    pub struct ExerciseProgressionPoint {
+        #[serde(rename = "workoutId")]
+        pub workout_id: i64,
+        #[serde(rename = "startedUtcSeconds")]
+        pub started_utc_s: i64,
+        #[serde(rename = "e1RM")]
+        pub e1rm: f64,
+    }
+
+    impl From<ExerciseProgressionPointEntity> for ExerciseProgressionPoint {
+        fn from(value: ExerciseProgressionPointEntity) -> Self {
+            Self {
+                workout_id: value.workout_id,
+                started_utc_s: value.started.timestamp(),
+                e1rm: value.e1rm,
+            }
+        }
+    }
+
+    #[derive(Debug, Serialize, ToSchema)]
+    pub struct ExerciseRecords {
+        #[serde(rename = "exerciseId")]
+        pub exercise_id: i64,
+        #[serde(rename = "heaviestWeight")]
+        pub heaviest_weight: i64,
+        #[serde(rename = "weightUnit")]
+        pub weight_unit: super::requests::WeightUnit,
+        #[serde(rename = "highestReps")]
+        pub highest_repetitions: i64,
+        #[serde(rename = "bestE1RM")]
+        pub best_e1rm: f64,
+        pub progression: Vec<ExerciseProgressionPoint>,
+    }
+
+    impl From<ExerciseRecordsEntity> for ExerciseRecords {
+        fn from(value: ExerciseRecordsEntity) -> Self {
+            Self {
+                exercise_id: value.exercise_id,
+                heaviest_weight: value.heaviest_weight,
+                weight_unit: value.weight_unit.into(),
+                highest_repetitions: value.highest_repetitions,
+                best_e1rm: value.best_e1rm,
+                progression: value.progression.into_iter().map(Into::into).collect(),
+            }
+        }
+    }
+
+    #[derive(Debug, Serialize, ToSchema)]
     pub struct StatisticsOverview {
         #[serde(rename = "totalWorkouts")]
         total_workouts: i64,
@@ -543,6 +1780,11 @@ mod responses {
         total_repetitions: i64,
         #[serde(rename = "avgRepsPerSet")]
         avg_repetitions_per_set: i64,
+        /// Seconds since the Unix epoch at which this overview was computed.
+        /// `None` when it was computed synchronously for this request because
+        /// no cached value was available yet.
+        #[serde(rename = "computedUtcSeconds")]
+        computed_utc_s: Option<i64>,
     }
 
     impl From<StatisticsOverviewEntity> for StatisticsOverview {
@@ -554,7 +1796,352 @@ mod responses {
                 total_sets: value.total_sets,
                 total_repetitions: value.total_repetitions,
                 avg_repetitions_per_set: value.avg_repetitions_per_set,
+                computed_utc_s: None,
+            }
+        }
+    }
+
+    impl From<StatisticsCacheEntity> for StatisticsOverview {
+        fn from(value: StatisticsCacheEntity) -> Self {
+            Self {
+                total_workouts: value.total_workouts,
+                total_duration_s: value.total_duration_s,
+                avg_duration_s: value.avg_duration_s,
+                total_sets: value.total_sets,
+                total_repetitions: value.total_repetitions,
+                avg_repetitions_per_set: value.avg_repetitions_per_set,
+                computed_utc_s: Some(value.computed.timestamp()),
+            }
+        }
+    }
+
+    #[derive(Debug, Serialize, ToSchema)]
+    pub struct Measurement {
+        pub id: i64,
+        pub kind: String,
+        pub value: f64,
+        #[serde(rename = "recordedUtcSeconds")]
+        pub recorded_utc_s: i64,
+    }
+
+    impl From<MeasurementEntity> for Measurement {
+        fn from(value: MeasurementEntity) -> Self {
+            Self {
+                id: value.id,
+                kind: value.kind,
+                value: value.value,
+                recorded_utc_s: value.recorded.timestamp(),
+            }
+        }
+    }
+
+    #[derive(Debug, Serialize, ToSchema)]
+    pub struct BodyweightVolumePoint {
+        #[serde(rename = "workoutId")]
+        pub workout_id: i64,
+        #[serde(rename = "startedUtcSeconds")]
+        pub started_utc_s: i64,
+        pub bodyweight: Option<f64>,
+        #[serde(rename = "volumeKg")]
+        pub volume_kg: f64,
+    }
+
+    impl From<BodyweightVolumePointEntity> for BodyweightVolumePoint {
+        fn from(value: BodyweightVolumePointEntity) -> Self {
+            Self {
+                workout_id: value.workout_id,
+                started_utc_s: value.started.timestamp(),
+                bodyweight: value.bodyweight,
+                volume_kg: value.volume_kg,
             }
         }
     }
+
+    #[derive(Debug, Serialize, Deserialize, ToSchema)]
+    pub struct BackupExercise {
+        pub id: i64,
+        pub name: String,
+    }
+
+    #[derive(Debug, Serialize, Deserialize, ToSchema)]
+    pub struct BackupWorkout {
+        pub id: i64,
+        #[serde(rename = "startedUtcSeconds")]
+        pub started_utc_s: i64,
+        pub note: Option<String>,
+    }
+
+    #[derive(Debug, Serialize, Deserialize, ToSchema)]
+    pub struct BackupExerciseSet {
+        pub id: i64,
+        #[serde(rename = "exerciseId")]
+        pub exercise_id: i64,
+        #[serde(rename = "workoutId")]
+        pub workout_id: i64,
+        #[serde(rename = "createdUtcSeconds")]
+        pub created_utc_s: i64,
+        pub repetitions: i64,
+        pub weight: i64,
+        #[serde(rename = "weightUnit")]
+        pub weight_unit: super::requests::WeightUnit,
+        pub note: Option<String>,
+    }
+
+    /// A full, portable snapshot of a user's exercises, workouts, and
+    /// exercise sets, suitable for backup or migration to another instance.
+    #[derive(Debug, Serialize, Deserialize, ToSchema)]
+    pub struct Backup {
+        #[serde(rename = "schemaVersion")]
+        pub schema_version: i32,
+        #[serde(rename = "exportedUtcSeconds")]
+        pub exported_utc_s: i64,
+        pub exercises: Vec<BackupExercise>,
+        pub workouts: Vec<BackupWorkout>,
+        #[serde(rename = "exerciseSets")]
+        pub exercise_sets: Vec<BackupExerciseSet>,
+    }
+
+    impl From<BackupEntity> for Backup {
+        fn from(value: BackupEntity) -> Self {
+            Self {
+                schema_version: value.schema_version,
+                exported_utc_s: value.exported_at.timestamp(),
+                exercises: value
+                    .exercises
+                    .into_iter()
+                    .map(|exercise| BackupExercise {
+                        id: exercise.id,
+                        name: exercise.name,
+                    })
+                    .collect(),
+                workouts: value
+                    .workouts
+                    .into_iter()
+                    .map(|workout| BackupWorkout {
+                        id: workout.id,
+                        started_utc_s: workout.started.timestamp(),
+                        note: workout.note,
+                    })
+                    .collect(),
+                exercise_sets: value
+                    .exercise_sets
+                    .into_iter()
+                    .map(|set| BackupExerciseSet {
+                        id: set.id,
+                        exercise_id: set.exercise_id,
+                        workout_id: set.workout_id,
+                        created_utc_s: set.created.timestamp(),
+                        repetitions: set.repetitions,
+                        weight: set.weight,
+                        weight_unit: set.weight_unit.into(),
+                        note: set.note,
+                    })
+                    .collect(),
+            }
+        }
+    }
+
+    impl TryFrom<Backup> for BackupEntity {
+        type Error = anyhow::Error;
+
+        fn try_from(value: Backup) -> Result<Self, Self::Error> {
+            Ok(Self {
+                schema_version: value.schema_version,
+                exported_at: chrono::DateTime::from_timestamp(value.exported_utc_s, 0)
+                    .ok_or_else(|| anyhow::anyhow!("Backup has an invalid exportedUtcSeconds"))?,
+                exercises: value
+                    .exercises
+                    .into_iter()
+                    .map(|exercise| ExerciseEntity {
+                        id: exercise.id,
+                        name: exercise.name,
+                        has_image: false,
+                    })
+                    .collect(),
+                workouts: value
+                    .workouts
+                    .into_iter()
+                    .map(|workout| {
+                        Ok(WorkoutEntity {
+                            id: workout.id,
+                            started: chrono::DateTime::from_timestamp(workout.started_utc_s, 0)
+                                .ok_or_else(|| anyhow::anyhow!("Workout {} has an invalid startedUtcSeconds", workout.id))?,
+                            note: workout.note,
+                        })
+                    })
+                    .collect::<Result<Vec<_>, anyhow::Error>>()?,
+                exercise_sets: value
+                    .exercise_sets
+                    .into_iter()
+                    .map(|set| {
+                        Ok(ExerciseSetEntity {
+                            id: set.id,
+                            exercise_id: set.exercise_id,
+                            exercise_name: String::new(),
+                            workout_id: set.workout_id,
+                            created: chrono::DateTime::from_timestamp(set.created_utc_s, 0)
+                                .ok_or_else(|| anyhow::anyhow!("Set {} has an invalid createdUtcSeconds", set.id))?,
+                            repetitions: set.repetitions,
+                            weight: set.weight,
+                            weight_unit: set.weight_unit.into(),
+                            note: set.note,
+                        })
+                    })
+                    .collect::<Result<Vec<_>, anyhow::Error>>()?,
+            })
+        }
+    }
+
+    #[derive(Debug, Serialize, ToSchema)]
+    pub struct ImportSummary {
+        #[serde(rename = "exercisesImported")]
+        pub exercises_imported: i64,
+        #[serde(rename = "workoutsImported")]
+        pub workouts_imported: i64,
+        #[serde(rename = "setsImported")]
+        pub sets_imported: i64,
+        #[serde(rename = "setsSkipped")]
+        pub sets_skipped: i64,
+    }
+
+    impl From<ImportSummaryEntity> for ImportSummary {
+        fn from(value: ImportSummaryEntity) -> Self {
+            Self {
+                exercises_imported: value.exercises_imported,
+                workouts_imported: value.workouts_imported,
+                sets_imported: value.sets_imported,
+                sets_skipped: value.sets_skipped,
+            }
+        }
+    }
+}
+
+mod events {
+    use axum::response::sse::Event;
+
+    use super::responses::ExerciseSet;
+
+    /// An update to an exercise set, broadcast to everyone watching the
+    /// owning workout's live stream.
+    #[derive(Debug, Clone)]
+    pub struct SetEvent {
+        pub user_id: i64,
+        pub workout_id: i64,
+        kind: SetEventKind,
+    }
+
+    #[derive(Debug, Clone)]
+    enum SetEventKind {
+        Created(ExerciseSet),
+        Updated(ExerciseSet),
+        Deleted { id: i64 },
+    }
+
+    impl SetEvent {
+        pub fn created(user_id: i64, set: ExerciseSet) -> Self {
+            Self {
+                user_id,
+                workout_id: set.workout_id,
+                kind: SetEventKind::Created(set),
+            }
+        }
+
+        pub fn updated(user_id: i64, set: ExerciseSet) -> Self {
+            Self {
+                user_id,
+                workout_id: set.workout_id,
+                kind: SetEventKind::Updated(set),
+            }
+        }
+
+        pub fn deleted(user_id: i64, workout_id: i64, id: i64) -> Self {
+            Self {
+                user_id,
+                workout_id,
+                kind: SetEventKind::Deleted { id },
+            }
+        }
+
+        /// Converts this event into a named SSE event, serializing its
+        /// payload as JSON.
+        pub fn into_sse_event(self) -> Event {
+            match self.kind {
+                SetEventKind::Created(set) => Event::default().event("created").json_data(set),
+                SetEventKind::Updated(set) => Event::default().event("updated").json_data(set),
+                SetEventKind::Deleted { id } => {
+                    Event::default().event("deleted").json_data(serde_json::json!({ "id": id }))
+                }
+            }
+            .expect("exercise set events are always serializable")
+        }
+    }
+}
+
+mod openapi {
+    use utoipa::OpenApi;
+
+    use super::{requests, responses};
+
+    #[derive(Debug, OpenApi)]
+    #[openapi(
+        paths(
+            super::get_exercise,
+            super::get_exercises,
+            super::create_exercise,
+            super::update_exercise,
+            super::delete_exercise,
+            super::restore_exercise,
+            super::get_exercise_count,
+            super::get_exercise_records,
+            super::upload_exercise_image,
+            super::get_exercise_image,
+            super::get_workout,
+            super::get_workouts,
+            super::create_workout,
+            super::delete_workout,
+            super::restore_workout,
+            super::get_exercise_set,
+            super::get_exercise_sets,
+            super::get_exercise_sets_by_workout_id,
+            super::get_exercise_sets_by_exercise_id,
+            super::search_exercise_sets,
+            super::create_exercise_set,
+            super::update_exercise_set,
+            super::delete_exercise_set,
+            super::restore_exercise_set,
+            super::get_set_suggestion,
+            super::get_statistics_overview,
+            super::get_bodyweight_vs_training_volume,
+            super::create_measurement,
+            super::get_measurements,
+            super::update_measurement,
+            super::delete_measurement,
+            super::export_backup,
+            super::import_backup,
+        ),
+        components(schemas(
+            requests::WeightUnit,
+            requests::CreateUpdateExercise,
+            requests::CreateUpdateExerciseSet,
+            requests::GetSetSuggestion,
+            requests::CreateMeasurement,
+            requests::UpdateMeasurement,
+            responses::Exercise,
+            responses::Workout,
+            responses::ExerciseSet,
+            responses::SetSuggestion,
+            responses::ExerciseCount,
+            responses::ExerciseRecords,
+            responses::ExerciseProgressionPoint,
+            responses::StatisticsOverview,
+            responses::Measurement,
+            responses::BodyweightVolumePoint,
+            responses::Backup,
+            responses::BackupExercise,
+            responses::BackupWorkout,
+            responses::BackupExerciseSet,
+            responses::ImportSummary,
+        ))
+    )]
+    pub struct ApiDoc;
 }